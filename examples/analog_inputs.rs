@@ -5,21 +5,25 @@ use std::time::Duration;
 
 fn main() {
     let mut pixtend = PiXtend::new().unwrap();
+    for i in 0..=3 {
+        pixtend
+            .set_reference_voltage(i, ReferenceVoltage::V10)
+            .unwrap();
+    }
+
     loop {
         pixtend.read_write().unwrap();
         for i in 0..=3 {
             println!(
                 "Analog voltage input {}: {}V",
                 i,
-                pixtend
-                    .get_analog_voltage_input(i, ReferenceVoltage::V10)
-                    .unwrap()
+                pixtend.get_analog_voltage_input(i).unwrap()
             );
         }
 
         for i in 4..=5 {
             println!(
-                "Analog current input {}: {}A",
+                "Analog current input {}: {}mA",
                 i,
                 pixtend.get_analog_current_input(i).unwrap()
             );
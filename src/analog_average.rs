@@ -0,0 +1,75 @@
+use std::collections::VecDeque;
+
+pub const DEFAULT_ANALOG_AVERAGE_SIZE: usize = 8;
+
+/// Ring buffer of the raw analog input values of the last cycles
+#[derive(Debug)]
+pub struct AnalogAverage {
+    samples: VecDeque<[u16; 6]>,
+    size: usize,
+}
+
+impl AnalogAverage {
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            samples: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+
+    /// Changes the number of buffered samples, dropping the oldest samples if necessary
+    pub fn set_size(&mut self, size: usize) {
+        self.size = size.max(1);
+        while self.samples.len() > self.size {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, sample: [u16; 6]) {
+        if self.samples.len() == self.size {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Returns the mean raw value of the input with the given index or `None` if no samples
+    /// have been collected yet
+    pub fn mean(&self, index: usize) -> Option<f64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+
+        let sum: f64 = self.samples.iter().map(|sample| sample[index] as f64).sum();
+        Some(sum / self.samples.len() as f64)
+    }
+}
+
+impl Default for AnalogAverage {
+    fn default() -> Self {
+        Self::new(DEFAULT_ANALOG_AVERAGE_SIZE)
+    }
+}
+
+#[test]
+fn test_analog_average() {
+    let mut average = AnalogAverage::new(2);
+    assert_eq!(average.mean(0), None);
+
+    average.push([10, 0, 0, 0, 0, 100]);
+    assert_eq!(average.mean(0), Some(10.0));
+    average.push([20, 0, 0, 0, 0, 200]);
+    assert_eq!(average.mean(0), Some(15.0));
+    average.push([40, 0, 0, 0, 0, 300]);
+    assert_eq!(average.mean(0), Some(30.0));
+    assert_eq!(average.mean(5), Some(250.0));
+
+    average.set_size(1);
+    assert_eq!(average.mean(0), Some(40.0));
+    average.clear();
+    assert_eq!(average.mean(0), None);
+}
@@ -0,0 +1,58 @@
+/// Exponential moving average low-pass filter of the raw analog input values
+#[derive(Debug)]
+pub struct AnalogFilter {
+    values: Option<[f64; 6]>,
+    alphas: [f64; 6],
+}
+
+impl AnalogFilter {
+    /// Sets the weight of a new sample for the input with the given index, where `1.0` passes
+    /// the samples through unfiltered
+    pub fn set_alpha(&mut self, index: usize, alpha: f64) {
+        self.alphas[index] = alpha;
+    }
+
+    /// Feeds the sample into the filter, the first sample initializes the filtered values
+    pub fn push(&mut self, sample: [u16; 6]) {
+        let values = self.values.get_or_insert(sample.map(|raw| raw as f64));
+        for ((value, raw), alpha) in values.iter_mut().zip(sample).zip(self.alphas) {
+            *value += alpha * (raw as f64 - *value);
+        }
+    }
+
+    /// Drops the filtered values, so the next sample initializes the filter again. The weights
+    /// are kept.
+    pub fn clear(&mut self) {
+        self.values = None;
+    }
+
+    /// Returns the filtered raw value of the input with the given index or `None` if no samples
+    /// have been collected yet
+    pub fn value(&self, index: usize) -> Option<f64> {
+        self.values.map(|values| values[index])
+    }
+}
+
+impl Default for AnalogFilter {
+    fn default() -> Self {
+        Self {
+            values: None,
+            alphas: [1.0; 6],
+        }
+    }
+}
+
+#[test]
+fn test_analog_filter() {
+    let mut filter = AnalogFilter::default();
+    filter.set_alpha(0, 0.5);
+    assert_eq!(filter.value(0), None);
+
+    filter.push([100, 100, 0, 0, 0, 0]);
+    assert_eq!(filter.value(0), Some(100.0));
+    filter.push([200, 200, 0, 0, 0, 0]);
+    assert_eq!(filter.value(0), Some(150.0));
+    assert_eq!(filter.value(1), Some(200.0));
+    filter.push([200, 300, 0, 0, 0, 0]);
+    assert_eq!(filter.value(0), Some(175.0));
+}
@@ -0,0 +1,204 @@
+use crate::{
+    error::PiXtendError,
+    pixtend::{COMMUNICATION_DELAY, SPI_CLOCK_SPEED, SPI_ENABLE_PIN},
+    Model, PiXtend, Watchdog,
+};
+use rppal::spi::{Bus, SlaveSelect};
+use std::time::Duration;
+
+/// Builder for a `PiXtend` instance, which applies the system settings before the first cycle
+/// is performed.
+///
+/// # Example
+/// ```no_run
+/// # use pixtend::{PiXtendBuilder, Watchdog};
+/// let mut pixtend = PiXtendBuilder::new()
+///     .watchdog(Watchdog::Activated1s)
+///     .gpio_pullup_enable(true)
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug)]
+pub struct PiXtendBuilder {
+    model: Model,
+    watchdog: Watchdog,
+    led_disabled: bool,
+    gpio_pullup_enable: bool,
+    retain_enable: bool,
+    retain_copy_check: bool,
+    clock_speed: u32,
+    spi_bus: Bus,
+    pixtend_ss: SlaveSelect,
+    dac_ss: SlaveSelect,
+    spi_enable_pin: Option<u8>,
+    cycle_delay: Duration,
+    reset_on_drop: bool,
+    dac_enabled: bool,
+}
+
+impl PiXtendBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the PiXtend model, which is sent in the output frame and expected in the input
+    /// frame. Currently only the frame layout of the `Model::LModel` is supported, `read_write`
+    /// returns `PiXtendError::UnsupportedModel` for other models.
+    pub fn model(mut self, model: Model) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Sets the watchdog timeout, see `PiXtend::set_watchdog`.
+    pub fn watchdog(mut self, watchdog: Watchdog) -> Self {
+        self.watchdog = watchdog;
+        self
+    }
+
+    /// Disables the status LED, see `PiXtend::set_led_disable`.
+    pub fn led_disabled(mut self, value: bool) -> Self {
+        self.led_disabled = value;
+        self
+    }
+
+    /// Globally enables the GPIO pullup resistors, see `PiXtend::set_gpio_pullup_enable`.
+    pub fn gpio_pullup_enable(mut self, value: bool) -> Self {
+        self.gpio_pullup_enable = value;
+        self
+    }
+
+    /// Enables the retain function, see `PiXtend::set_retain_enable`.
+    pub fn retain_enable(mut self, value: bool) -> Self {
+        self.retain_enable = value;
+        self
+    }
+
+    /// Configures whether a retain copy without the retain function being enabled is rejected,
+    /// see `PiXtend::set_retain_copy_check`.
+    pub fn retain_copy_check(mut self, value: bool) -> Self {
+        self.retain_copy_check = value;
+        self
+    }
+
+    /// Sets the SPI clock speed in Hz, see `PiXtend::with_clock_speed`.
+    pub fn clock_speed(mut self, speed_hz: u32) -> Self {
+        self.clock_speed = speed_hz;
+        self
+    }
+
+    /// Sets the SPI bus that the PiXtend board and the DAC are connected to. The default is
+    /// `Bus::Spi0`.
+    pub fn spi_bus(mut self, bus: Bus) -> Self {
+        self.spi_bus = bus;
+        self
+    }
+
+    /// Sets the slave select of the PiXtend board on the SPI bus. The default is
+    /// `SlaveSelect::Ss0`.
+    pub fn pixtend_ss(mut self, slave_select: SlaveSelect) -> Self {
+        self.pixtend_ss = slave_select;
+        self
+    }
+
+    /// Sets the slave select of the DAC on the SPI bus. The default is `SlaveSelect::Ss1`.
+    pub fn dac_ss(mut self, slave_select: SlaveSelect) -> Self {
+        self.dac_ss = slave_select;
+        self
+    }
+
+    /// Sets the GPIO pin that is driven high to enable the communication with the PiXtend board.
+    /// `None` skips the GPIO manipulation entirely, i.e. if the enable signal is managed
+    /// externally. The default is GPIO 24.
+    pub fn spi_enable_pin(mut self, pin: Option<u8>) -> Self {
+        self.spi_enable_pin = pin;
+        self
+    }
+
+    /// Sets the minimum delay between two cycles, see `PiXtend::set_cycle_delay`.
+    pub fn cycle_delay(mut self, delay: Duration) -> Self {
+        self.cycle_delay = delay;
+        self
+    }
+
+    /// Puts the PiXtend into the safe state when the instance is dropped, see
+    /// `PiXtend::set_reset_on_drop`.
+    pub fn reset_on_drop(mut self, value: bool) -> Self {
+        self.reset_on_drop = value;
+        self
+    }
+
+    /// Configures whether the analog output values are written to the DAC in every cycle, see
+    /// `PiXtend::set_dac_enabled`.
+    pub fn dac_enabled(mut self, value: bool) -> Self {
+        self.dac_enabled = value;
+        self
+    }
+
+    /// Validates the collected settings and creates the `PiXtend` instance.
+    /// Returns an error if the clock speed is `0`, if the PiXtend board and the DAC use the same
+    /// slave select or if the SPI / GPIO initialization fails.
+    pub fn build(self) -> Result<PiXtend, PiXtendError> {
+        if self.clock_speed == 0 {
+            return Err(PiXtendError::InvalidSpiClockSpeed(self.clock_speed));
+        }
+
+        if self.pixtend_ss == self.dac_ss {
+            return Err(PiXtendError::SpiSlaveSelectConflict(self.pixtend_ss));
+        }
+
+        let mut pixtend = PiXtend::with_spi(
+            self.clock_speed,
+            self.spi_bus,
+            self.pixtend_ss,
+            self.dac_ss,
+            self.spi_enable_pin,
+        )?;
+
+        pixtend.output.header.model = self.model;
+
+        // The global settings are applied first, since the GPIO configuration depends on them
+        pixtend.set_gpio_pullup_enable(self.gpio_pullup_enable);
+        pixtend.set_retain_enable(self.retain_enable);
+        pixtend.set_retain_copy_check(self.retain_copy_check);
+        pixtend.set_led_disable(self.led_disabled);
+        pixtend.set_watchdog(self.watchdog);
+        pixtend.set_cycle_delay(self.cycle_delay);
+        pixtend.set_reset_on_drop(self.reset_on_drop);
+        pixtend.set_dac_enabled(self.dac_enabled);
+
+        Ok(pixtend)
+    }
+}
+
+impl Default for PiXtendBuilder {
+    fn default() -> Self {
+        Self {
+            model: Model::default(),
+            watchdog: Watchdog::default(),
+            led_disabled: false,
+            gpio_pullup_enable: false,
+            retain_enable: false,
+            retain_copy_check: true,
+            clock_speed: SPI_CLOCK_SPEED,
+            spi_bus: Bus::Spi0,
+            pixtend_ss: SlaveSelect::Ss0,
+            dac_ss: SlaveSelect::Ss1,
+            spi_enable_pin: Some(SPI_ENABLE_PIN),
+            cycle_delay: COMMUNICATION_DELAY,
+            reset_on_drop: false,
+            dac_enabled: true,
+        }
+    }
+}
+
+#[test]
+fn test_slave_select_conflict() {
+    let result = PiXtendBuilder::new()
+        .pixtend_ss(SlaveSelect::Ss1)
+        .dac_ss(SlaveSelect::Ss1)
+        .build();
+    assert!(matches!(
+        result,
+        Err(PiXtendError::SpiSlaveSelectConflict(SlaveSelect::Ss1))
+    ));
+}
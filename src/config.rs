@@ -0,0 +1,20 @@
+use crate::{GpioConfig, PwmConfig, Watchdog};
+
+/// Collection of all settable configuration options of the PiXtend, which can be read via
+/// `PiXtend::get_config` and applied via `PiXtend::apply_config`. With the `serde` feature
+/// enabled, it can be stored in and loaded from configuration files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PiXtendConfig {
+    pub watchdog: Watchdog,
+    pub retain_copy: bool,
+    pub retain_enable: bool,
+    pub led_disable: bool,
+    pub gpio_pullup_enable: bool,
+    /// Debounce cycles of the 8 digital input groups
+    pub digital_debounce: [u8; 8],
+    /// Debounce cycles of the 2 GPIO input groups
+    pub gpio_debounce: [u8; 2],
+    pub gpio_configs: [GpioConfig; 4],
+    pub pwm_configs: [PwmConfig; 3],
+}
@@ -1,39 +1,80 @@
+use crate::{Channel, Model};
 use deku::DekuError;
-use rppal::{gpio::Error as GpioError, spi::Error as SpiError};
+#[cfg(feature = "std")]
+use rppal::{
+    gpio::Error as GpioError,
+    spi::{Error as SpiError, SlaveSelect},
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum PiXtendError {
+    #[cfg(feature = "std")]
     #[error("GPIO error: {0}")]
     GpioError(#[from] GpioError),
+    #[cfg(feature = "std")]
     #[error("SPI error: {0}")]
     SpiError(#[from] SpiError),
+    #[cfg(feature = "std")]
+    #[error("Recording error: {0}")]
+    RecordingError(#[from] std::io::Error),
     #[error("Binary frame error: {0}")]
-    BinaryFrameReadWriteError(#[from] DekuError),
+    BinaryFrameReadWriteError(#[cfg_attr(feature = "std", source)] DekuError),
     #[error("Invalid digital output index: {0}")]
     InvalidDigitalOutputIndex(u8),
+    #[error("Invalid digital output mask, only bits 0 to 11 are valid: {0:#06x}")]
+    InvalidDigitalOutputMask(u16),
     #[error("Invalid digital debounce group: {0}")]
     InvalidDigitalDebounceGroup(u8),
+    #[error("Debounce time is out of range, at most 255 cycles are valid: {0}ms")]
+    DebounceTimeOutOfRange(u32),
     #[error("Invalid relay output index: {0}")]
     InvalidRelayOutputIndex(u8),
+    #[error("Invalid relay output mask, only bits 0 to 3 are valid: {0:#04x}")]
+    InvalidRelayOutputMask(u8),
     #[error("Invalid gpio output index: {0}")]
     InvalidGpioOutputIndex(u8),
     #[error("Cannot enable GPIO pullup resistor without enabling it globally")]
     GpioPullupNotGloballyEnabled,
     #[error("GPIO not configured as output: {0}")]
     GpioNotConfiguredAsOutput(u8),
+    #[error("GPIO is configured as a sensor and can't be used as an output: {0}")]
+    GpioConfiguredAsSensor(u8),
     #[error("GPIO not configured as input: {0}")]
     GpioNotConfiguredAsInput(u8),
+    #[error("GPIO not configured as sensor: {0}")]
+    GpioNotConfiguredAsSensor(u8),
     #[error("Invalid gpio debounce group: {0}")]
     InvalidGpioDebounceGroup(u8),
     #[error("Invalid retain data length: {0}")]
     InvalidRetainDataLength(usize),
+    #[error(
+        "Serialized retain data is too large, at most {size} bytes are available: {0}",
+        size = crate::RETAIN_SIZE
+    )]
+    RetainDataTooLarge(usize),
+    #[cfg(feature = "serde")]
+    #[error("Retain data serialization error: {0}")]
+    RetainSerializationError(#[from] postcard::Error),
     #[error("Cannot write retain data without enabling it globally")]
     RetainDataNotGloballyEnabled,
-    #[error("Invalid SPI response length: {0}")]
-    InvalidSpiResponseLength(usize),
+    #[error("Retain copy is set, but the retain function is not enabled globally")]
+    RetainCopyWithoutEnable,
+    #[error("Invalid watchdog tier, only 0 to 10 are valid: {0}")]
+    InvalidWatchdogTier(u8),
+    #[error("Invalid SPI clock speed: {0}Hz")]
+    InvalidSpiClockSpeed(u32),
+    #[cfg(feature = "std")]
+    #[error("PiXtend and DAC must use different SPI slave selects, both use {0:?}")]
+    SpiSlaveSelectConflict(SlaveSelect),
+    #[error("Invalid SPI response length, expected {expected} bytes but received {actual}")]
+    InvalidSpiResponseLength { expected: usize, actual: usize },
+    #[error("Firmware version {found} is too old, at least {required} is required")]
+    FirmwareTooOld { found: u8, required: u8 },
     #[error("Invalid PiXtend model")]
     PiXtendModelMismatch,
+    #[error("Frame layout of PiXtend model {0:?} is not supported")]
+    UnsupportedModel(Model),
     #[error("CRC Error occured in input data")]
     InputCrcError,
     #[error("PiXtend reports crc error in output data")]
@@ -44,24 +85,108 @@ pub enum PiXtendError {
     DataBlockTooShort,
     #[error("PiXtend not ready for communication, maybe it is in safe mode")]
     NotReadyForCommunication,
+    #[error("PiXtend returned {0} identical input frames, the link seems to be stalled")]
+    LinkStalled(usize),
+    #[error("Invalid stall detection threshold, at least 2 frames are required: {0}")]
+    InvalidStallThreshold(usize),
     #[error("No input data available, fetch using `read_write` first")]
     NoInputDataAvailable,
+    #[error("The background poller has stopped")]
+    PollerStopped,
     #[error("Invalid digital input index: {0}")]
     InvalidDigitalInputIndex(u8),
     #[error("Invalid analog input index: {0}")]
     InvalidAnalogVoltageInputIndex(u8),
     #[error("Invalid analog current index: {0}")]
     InvalidAnalogCurrentInputIndex(u8),
+    #[error("Invalid analog filter weight, must be between 0.0 and 1.0: {0}")]
+    InvalidAnalogFilterAlpha(f64),
+    #[error("Current loop on input {index} is broken, only {milliamperes}mA are flowing")]
+    CurrentLoopBroken { index: u8, milliamperes: f64 },
     #[error("Invalid gpio input index: {0}")]
     InvalidGpioInputIndex(u8),
+    #[error("Sensor on GPIO {index} is not responding")]
+    SensorNotResponding { index: u8 },
+    #[error("Implausible sensor reading on GPIO {index}")]
+    SensorReadImplausible { index: u8 },
     #[error("PWM and DHT are exclusive, cannot use both at the same time")]
     PwmAndDhtExclusive,
     #[error("Invalid PWM output group index: {0}")]
     InvalidPwmOutputGroupIndex(u8),
+    #[error("PWM group is deactivated: {0}")]
+    PwmGroupDeactivated(u8),
+    #[error("PWM channel {channel:?} of group {index} can't be set in the configured mode")]
+    PwmChannelNotSettable { index: u8, channel: Channel },
     #[error("PWM not configured as servo: {0}")]
     PwmNotConfiguredAsServo(u8),
-    #[error("PWM not configured as duty cycle or tried to set channel B for universal {0}")]
+    #[error("PWM servo value of group {index} is out of range, at most 16000 is valid: {value}")]
+    PwmServoValueOutOfRange { index: u8, value: u16 },
+    #[error("Invalid servo range for PWM group {0}, minimum must be smaller than maximum")]
+    InvalidServoRange(u8),
+    #[error(
+        "Invalid servo calibration for PWM group {0}, pulse widths must be within 1000µs to 2000µs"
+    )]
+    InvalidServoCalibration(u8),
+    #[error("PWM not configured for duty cycle: {0}")]
     PwmNotConfiguredForDutyCycle(u8),
     #[error("PWM not configured as frequency: {0}")]
     PwmNotConfiguredAsFrequency(u8),
+    #[error("PWM group has no configured frequency, it is deactivated or a servo group: {0}")]
+    PwmFrequencyNotConfigured(u8),
+    #[error("PWM frequency of group {index} is out of range: {hz}Hz")]
+    PwmFrequencyOutOfRange { index: u8, hz: f64 },
+    #[error("PWM prescaler is deactivated: {0}")]
+    PwmPrescalerDeactivated(u8),
+    #[error("No PWM prescaler and frequency value can represent the frequency: {hz}Hz")]
+    PwmFrequencyNotAvailable { hz: f64 },
+    #[error("PWM frequency of group {index} can't be represented by its prescaler: {hz}Hz")]
+    PwmFrequencyUnreachable { index: u8, hz: f64 },
+}
+
+// `DekuError` only implements `Error` with `std`, so the conversion can't be derived via `#[from]`
+impl From<DekuError> for PiXtendError {
+    fn from(error: DekuError) -> Self {
+        PiXtendError::BinaryFrameReadWriteError(error)
+    }
+}
+
+impl PiXtendError {
+    /// Returns whether the error is caused by a transient communication fault, i.e. a corrupted
+    /// or incomplete frame, so that retrying the cycle may succeed. Errors caused by the
+    /// configuration, the connected model or the safe state require an intervention and return
+    /// `false`.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            #[cfg(feature = "std")]
+            PiXtendError::SpiError(_) => true,
+            PiXtendError::InputCrcError
+            | PiXtendError::OutputCrcError
+            | PiXtendError::InvalidSpiResponseLength { .. }
+            | PiXtendError::DataBlockTooShort => true,
+            _ => false,
+        }
+    }
+}
+
+#[test]
+fn test_is_recoverable() {
+    assert!(PiXtendError::InputCrcError.is_recoverable());
+    assert!(PiXtendError::InvalidSpiResponseLength {
+        expected: 111,
+        actual: 50
+    }
+    .is_recoverable());
+    assert!(!PiXtendError::PiXtendModelMismatch.is_recoverable());
+    assert!(!PiXtendError::NotReadyForCommunication.is_recoverable());
+    assert!(!PiXtendError::PwmAndDhtExclusive.is_recoverable());
+}
+
+#[test]
+fn test_retain_data_too_large_message() {
+    use alloc::string::ToString;
+
+    assert_eq!(
+        PiXtendError::RetainDataTooLarge(70).to_string(),
+        "Serialized retain data is too large, at most 64 bytes are available: 70"
+    );
 }
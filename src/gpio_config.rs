@@ -1,5 +1,6 @@
 /// GPIO configuration options
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GpioConfig {
     /// GPIO Input with optional pullup resistor
     Input(bool),
@@ -0,0 +1,53 @@
+use crate::{error::PiXtendError, transport::SpiTransport, PiXtend};
+use embedded_hal::digital::{Error, ErrorKind, ErrorType, OutputPin};
+use rppal::spi::Spi;
+
+/// Adapter that exposes a single digital output of the PiXtend as an `embedded_hal` output pin,
+/// see `PiXtend::digital_output_pin`. Like `PiXtend::set_digital_output`, setting the pin only
+/// stages the value, which takes effect with the next `read_write` or `write`.
+pub struct DigitalOutputPin<'a, T: SpiTransport = Spi> {
+    pixtend: &'a mut PiXtend<T>,
+    index: u8,
+}
+
+impl<'a, T: SpiTransport> DigitalOutputPin<'a, T> {
+    pub(crate) fn new(pixtend: &'a mut PiXtend<T>, index: u8) -> Self {
+        Self { pixtend, index }
+    }
+}
+
+impl<T: SpiTransport> ErrorType for DigitalOutputPin<'_, T> {
+    type Error = PiXtendError;
+}
+
+impl<T: SpiTransport> OutputPin for DigitalOutputPin<'_, T> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.pixtend.set_digital_output(self.index, false)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.pixtend.set_digital_output(self.index, true)
+    }
+}
+
+impl Error for PiXtendError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+#[test]
+fn test_digital_output_pin() {
+    let mut pixtend = PiXtend::mocked([]);
+    let mut pin = pixtend.digital_output_pin(5).unwrap();
+    pin.set_high().unwrap();
+    assert_eq!(pixtend.get_digital_output(5).unwrap(), true);
+
+    let mut pin = pixtend.digital_output_pin(5).unwrap();
+    pin.set_low().unwrap();
+    assert_eq!(pixtend.get_digital_output(5).unwrap(), false);
+    assert!(matches!(
+        pixtend.digital_output_pin(12),
+        Err(PiXtendError::InvalidDigitalOutputIndex(12))
+    ));
+}
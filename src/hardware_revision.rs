@@ -0,0 +1,51 @@
+/// Hardware revision of a PiXtend V2 board, which the board reports as a single byte with the
+/// major version in the tens and the minor version in the ones place, i.e. `21` for V2.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HardwareRevision {
+    /// Revision `major.minor` of the PiXtend V2 series
+    Version { major: u8, minor: u8 },
+    /// Value that doesn't belong to a PiXtend V2 board
+    Unknown(u8),
+}
+
+impl HardwareRevision {
+    /// Decodes the hardware version byte of the input header
+    pub fn from_byte(byte: u8) -> Self {
+        match byte {
+            20..=29 => HardwareRevision::Version {
+                major: byte / 10,
+                minor: byte % 10,
+            },
+            _ => HardwareRevision::Unknown(byte),
+        }
+    }
+
+    /// Returns whether this is a known revision of at least `major.minor`, i.e. to gate features
+    /// that were added in a later revision. An unknown revision never satisfies the check.
+    pub fn at_least(&self, major: u8, minor: u8) -> bool {
+        match *self {
+            HardwareRevision::Version {
+                major: found_major,
+                minor: found_minor,
+            } => (found_major, found_minor) >= (major, minor),
+            HardwareRevision::Unknown(_) => false,
+        }
+    }
+}
+
+#[test]
+fn test_hardware_revision() {
+    let revision = HardwareRevision::from_byte(21);
+    assert_eq!(revision, HardwareRevision::Version { major: 2, minor: 1 });
+    assert!(revision.at_least(2, 0));
+    assert!(revision.at_least(2, 1));
+    assert!(!revision.at_least(2, 2));
+
+    assert_eq!(HardwareRevision::from_byte(0), HardwareRevision::Unknown(0));
+    assert_eq!(
+        HardwareRevision::from_byte(31),
+        HardwareRevision::Unknown(31)
+    );
+    assert!(!HardwareRevision::Unknown(31).at_least(0, 0));
+}
@@ -0,0 +1,69 @@
+/// Defines an index newtype that can only be constructed for values up to the given maximum
+macro_rules! checked_index {
+    ($(#[$meta:meta])* $name:ident, $max:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+        pub struct $name(u8);
+
+        impl $name {
+            /// Highest valid index
+            pub const MAX: u8 = $max;
+
+            /// Creates the index or returns `None` if it is out of range. In a constant, an
+            /// invalid index is caught at compile time when unwrapping.
+            pub const fn new(index: u8) -> Option<Self> {
+                match index <= Self::MAX {
+                    true => Some(Self(index)),
+                    false => None,
+                }
+            }
+
+            /// Returns the index as a plain number
+            pub const fn get(self) -> u8 {
+                self.0
+            }
+        }
+
+        impl From<$name> for u8 {
+            fn from(index: $name) -> Self {
+                index.0
+            }
+        }
+    };
+}
+
+checked_index!(
+    /// Index of a digital output from `0` to `11`
+    DigitalOutputIndex,
+    11
+);
+checked_index!(
+    /// Index of a relay output from `0` to `3`
+    RelayIndex,
+    3
+);
+checked_index!(
+    /// Index of a PWM group from `0` to `2`
+    PwmGroupIndex,
+    2
+);
+checked_index!(
+    /// Index of a GPIO from `0` to `3`
+    GpioIndex,
+    3
+);
+
+#[test]
+fn test_checked_index() {
+    const RELAY: RelayIndex = match RelayIndex::new(3) {
+        Some(index) => index,
+        None => panic!("invalid relay index"),
+    };
+    assert_eq!(RELAY.get(), 3);
+    assert!(RelayIndex::new(4).is_none());
+    assert_eq!(DigitalOutputIndex::new(11).map(u8::from), Some(11));
+    assert!(DigitalOutputIndex::new(12).is_none());
+    assert!(PwmGroupIndex::new(2).is_some());
+    assert!(PwmGroupIndex::new(3).is_none());
+    assert!(GpioIndex::new(4).is_none());
+}
@@ -1,4 +1,5 @@
-use crate::error::PiXtendError;
+use crate::{error::PiXtendError, utils::round};
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuWrite, DekuRead)]
@@ -23,32 +24,98 @@ impl AnalogIn {
         index: u8,
         reference_voltage: ReferenceVoltage,
     ) -> Result<f64, PiXtendError> {
-        let reference_voltage = match reference_voltage {
-            ReferenceVoltage::V5 => 5.0,
-            ReferenceVoltage::V10 => 10.0,
-        };
         match index {
-            0 => Ok(self.in0 as f64 * reference_voltage / 1024.0),
-            1 => Ok(self.in1 as f64 * reference_voltage / 1024.0),
-            2 => Ok(self.in2 as f64 * reference_voltage / 1024.0),
-            3 => Ok(self.in3 as f64 * reference_voltage / 1024.0),
+            0 => Ok(raw_to_voltage(self.in0 as f64, reference_voltage)),
+            1 => Ok(raw_to_voltage(self.in1 as f64, reference_voltage)),
+            2 => Ok(raw_to_voltage(self.in2 as f64, reference_voltage)),
+            3 => Ok(raw_to_voltage(self.in3 as f64, reference_voltage)),
             _ => Err(PiXtendError::InvalidAnalogVoltageInputIndex(index)),
         }
     }
 
     pub fn get_analog_current_input(&self, index: u8) -> Result<f64, PiXtendError> {
         match index {
-            4 => Ok(self.in4 as f64 * 0.020158400229358),
-            5 => Ok(self.in5 as f64 * 0.020158400229358),
+            4 => Ok(raw_to_current(self.in4 as f64)),
+            5 => Ok(raw_to_current(self.in5 as f64)),
             _ => Err(PiXtendError::InvalidAnalogCurrentInputIndex(index)),
         }
     }
+
+    pub fn get_analog_raw(&self, index: u8) -> Result<u16, PiXtendError> {
+        self.raw_values()
+            .get(index as usize)
+            .copied()
+            .ok_or(PiXtendError::InvalidAnalogVoltageInputIndex(index))
+    }
+
+    /// Returns whether the raw value of the given input is clipped at either end of the ADC range
+    pub fn is_saturated(&self, index: u8) -> Result<bool, PiXtendError> {
+        let raw = self.get_analog_raw(index)?;
+        Ok(raw == 0 || raw >= ADC_MAX_VALUE)
+    }
+
+    pub fn raw_values(&self) -> [u16; 6] {
+        [self.in0, self.in1, self.in2, self.in3, self.in4, self.in5]
+    }
+}
+
+/// Maximum raw value of the 10 bit ADC of the analog inputs
+const ADC_MAX_VALUE: u16 = 1023;
+
+/// Converts a (possibly averaged) raw value of a voltage input to volts
+pub fn raw_to_voltage(raw: f64, reference_voltage: ReferenceVoltage) -> f64 {
+    raw * reference_voltage.volts() / 1024.0
+}
+
+/// Converts a raw ADC count of a voltage input to volts, i.e. for raw values captured via
+/// `PiXtend::get_analog_raw` that are converted later.
+pub fn adc_to_voltage(raw: u16, reference_voltage: ReferenceVoltage) -> f64 {
+    raw_to_voltage(raw as f64, reference_voltage)
+}
+
+/// Converts a voltage to the raw ADC count a voltage input reports for it, which is the inverse
+/// of `adc_to_voltage`. The result is rounded and clamped to the 10 bit range of `0` to `1023`.
+pub fn voltage_to_adc(voltage: f64, reference_voltage: ReferenceVoltage) -> u16 {
+    let raw = round(voltage * 1024.0 / reference_voltage.volts());
+    raw.clamp(0.0, ADC_MAX_VALUE as f64) as u16
+}
+
+/// Scale of the current inputs in milliamperes per ADC count
+pub(crate) const CURRENT_SCALE: f64 = 0.020158400229358;
+
+/// Converts a (possibly averaged) raw value of a current input to milliamperes
+pub fn raw_to_current(raw: f64) -> f64 {
+    raw * CURRENT_SCALE
 }
 
 /// Reference voltage for analog inputs
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReferenceVoltage {
     /// 0V to 5V
     V5,
     /// 0V to 10V
     V10,
 }
+
+impl ReferenceVoltage {
+    fn volts(&self) -> f64 {
+        match self {
+            ReferenceVoltage::V5 => 5.0,
+            ReferenceVoltage::V10 => 10.0,
+        }
+    }
+}
+
+#[test]
+fn test_adc_voltage_conversion() {
+    assert_eq!(adc_to_voltage(512, ReferenceVoltage::V10), 5.0);
+    assert_eq!(adc_to_voltage(256, ReferenceVoltage::V5), 1.25);
+    assert_eq!(voltage_to_adc(5.0, ReferenceVoltage::V10), 512);
+    assert_eq!(voltage_to_adc(11.0, ReferenceVoltage::V10), 1023);
+    assert_eq!(voltage_to_adc(-1.0, ReferenceVoltage::V5), 0);
+    for raw in 0..=1023 {
+        let voltage = adc_to_voltage(raw, ReferenceVoltage::V5);
+        assert_eq!(voltage_to_adc(voltage, ReferenceVoltage::V5), raw);
+    }
+}
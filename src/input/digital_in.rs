@@ -1,4 +1,5 @@
 use crate::error::PiXtendError;
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuWrite, DekuRead)]
@@ -59,4 +60,18 @@ impl DigitalIn {
             _ => Err(PiXtendError::InvalidDigitalInputIndex(index)),
         }
     }
+
+    pub fn as_mask(&self) -> u16 {
+        (0..16)
+            .filter(|&index| self.get_digital_input(index).unwrap_or_default())
+            .fold(0, |mask, index| mask | (1 << index))
+    }
+}
+
+#[test]
+fn test_digital_in_mask() {
+    let data = [0b1000_0001, 0b0100_0010];
+    let (_, digital_in) = DigitalIn::from_bytes((data.as_ref(), 0)).unwrap();
+    assert_eq!(digital_in.as_mask(), 0b0100_0010_1000_0001);
+    assert_eq!(digital_in.to_bytes().unwrap(), data);
 }
@@ -1,4 +1,5 @@
 use crate::error::PiXtendError;
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuWrite, DekuRead)]
@@ -1,4 +1,5 @@
-use crate::utils::calc_crc16;
+use crate::{utils::calc_crc16, RETAIN_SIZE};
+use alloc::vec::Vec;
 use analog_in::AnalogIn;
 use deku::prelude::*;
 use digital_in::DigitalIn;
@@ -13,8 +14,14 @@ mod sensor_in;
 mod state;
 mod warnings;
 
-pub use analog_in::ReferenceVoltage;
-pub use sensor_in::SensorKind;
+#[cfg(feature = "std")]
+pub use analog_in::raw_to_voltage;
+#[cfg(feature = "std")]
+pub(crate) use analog_in::CURRENT_SCALE;
+pub use analog_in::{adc_to_voltage, voltage_to_adc, ReferenceVoltage};
+#[cfg(feature = "std")]
+pub(crate) use sensor_in::Sensor;
+pub use sensor_in::{SensorKind, TemperatureUnit};
 pub use state::ErrorCode;
 pub use warnings::Warnings;
 
@@ -53,6 +60,6 @@ pub struct Data {
     pub gpio_in: GpioIn,
     #[deku(pad_bytes_after = "5")]
     pub sensor_in: SensorIn,
-    #[deku(count = "64")]
+    #[deku(count = "RETAIN_SIZE")]
     pub retain: Vec<u8>,
 }
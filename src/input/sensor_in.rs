@@ -1,4 +1,6 @@
-use crate::error::PiXtendError;
+use crate::{error::PiXtendError, utils::round};
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
 use deku::prelude::*;
 
 #[derive(Debug, DekuWrite, DekuRead)]
@@ -9,7 +11,7 @@ pub struct SensorIn {
     pub sens3: Sensor,
 }
 
-#[derive(Debug, DekuWrite, DekuRead)]
+#[derive(Debug, DekuWrite, DekuRead, Clone, Copy)]
 pub struct Sensor {
     #[deku(endian = "little")]
     pub temperature: u16,
@@ -18,21 +20,69 @@ pub struct Sensor {
 }
 
 impl SensorIn {
+    pub fn sensor(&self, index: u8) -> Result<&Sensor, PiXtendError> {
+        match index {
+            0 => Ok(&self.sens0),
+            1 => Ok(&self.sens1),
+            2 => Ok(&self.sens2),
+            3 => Ok(&self.sens3),
+            _ => Err(PiXtendError::InvalidGpioInputIndex(index)),
+        }
+    }
+
+    pub fn sensors(&self) -> [&Sensor; 4] {
+        [&self.sens0, &self.sens1, &self.sens2, &self.sens3]
+    }
+
     pub fn get_temperature_input(
         &self,
         index: u8,
         sensor: SensorKind,
     ) -> Result<f64, PiXtendError> {
+        self.sensor(index)?
+            .plausible_temperature(sensor)
+            .ok_or(PiXtendError::SensorReadImplausible { index })
+    }
+
+    pub fn get_humidity_input(&self, index: u8, sensor: SensorKind) -> Result<f64, PiXtendError> {
+        self.sensor(index)?
+            .plausible_humidity(sensor)
+            .ok_or(PiXtendError::SensorReadImplausible { index })
+    }
+}
+
+impl Sensor {
+    /// Returns whether both registers are zero, which the PiXtend reports if no sensor responds
+    pub fn is_empty(&self) -> bool {
+        self.temperature == 0 && self.humidity == 0
+    }
+
+    /// Encodes a temperature in Celsius and a humidity fraction into the registers of the sensor
+    pub fn from_values(sensor: SensorKind, celsius: f64, humidity: f64) -> Self {
+        let (temperature, humidity) = match sensor {
+            SensorKind::DHT11 => (
+                round(celsius.max(0.0) * 256.0) as u16,
+                round(humidity * 25600.0) as u16,
+            ),
+            SensorKind::DHT22 | SensorKind::AM2302 => {
+                let sign = if celsius < 0.0 { 0x8000 } else { 0 };
+                let magnitude = (round(celsius.abs() * 10.0) as u16) & 0x7FFF;
+                (sign | magnitude, round(humidity * 1000.0) as u16)
+            }
+        };
+
+        Self {
+            temperature,
+            humidity,
+        }
+    }
+
+    /// Calculates the temperature in Celsius without any plausibility checks
+    pub fn temperature(&self, sensor: SensorKind) -> f64 {
         // A dht22 can be negative when the msb is set
         let negative = match sensor {
             SensorKind::DHT11 => false,
-            SensorKind::DHT22 => match index {
-                0 => self.sens0.temperature & 0x8000 != 0,
-                1 => self.sens1.temperature & 0x8000 != 0,
-                2 => self.sens2.temperature & 0x8000 != 0,
-                3 => self.sens3.temperature & 0x8000 != 0,
-                _ => return Err(PiXtendError::InvalidGpioInputIndex(index)),
-            },
+            SensorKind::DHT22 | SensorKind::AM2302 => self.temperature & 0x8000 != 0,
         };
 
         let factor = match negative {
@@ -42,35 +92,152 @@ impl SensorIn {
 
         let div = match sensor {
             SensorKind::DHT11 => 256.0,
-            SensorKind::DHT22 => 10.0,
+            SensorKind::DHT22 | SensorKind::AM2302 => 10.0,
         };
 
-        match index {
-            0 => Ok((self.sens0.temperature & 0x7FFF) as f64 / div * factor),
-            1 => Ok((self.sens1.temperature & 0x7FFF) as f64 / div * factor),
-            2 => Ok((self.sens2.temperature & 0x7FFF) as f64 / div * factor),
-            3 => Ok((self.sens3.temperature & 0x7FFF) as f64 / div * factor),
-            _ => Err(PiXtendError::InvalidGpioInputIndex(index)),
-        }
+        (self.temperature & 0x7FFF) as f64 / div * factor
     }
 
-    pub fn get_humidity_input(&self, index: u8, sensor: SensorKind) -> Result<f64, PiXtendError> {
+    /// Calculates the temperature in Celsius or `None` if it is outside of the measurement range
+    pub fn plausible_temperature(&self, sensor: SensorKind) -> Option<f64> {
+        Some(self.temperature(sensor))
+            .filter(|celsius| sensor.temperature_range().contains(celsius))
+    }
+
+    /// Calculates the humidity as a fraction or `None` if it is outside of 0.0 to 1.0
+    pub fn plausible_humidity(&self, sensor: SensorKind) -> Option<f64> {
+        Some(self.humidity(sensor)).filter(|humidity| (0.0..=1.0).contains(humidity))
+    }
+
+    /// Calculates the humidity as a fraction from 0.0 to 1.0 without any plausibility checks. The
+    /// DHT11 reports whole percent in the high byte and the decimal in the low byte, so its raw
+    /// value is divided by 256 for percent, the DHT22 reports tenths of a percent.
+    pub fn humidity(&self, sensor: SensorKind) -> f64 {
         let div = match sensor {
             SensorKind::DHT11 => 25600.0,
-            SensorKind::DHT22 => 1000.0,
+            SensorKind::DHT22 | SensorKind::AM2302 => 1000.0,
         };
 
-        match index {
-            0 => Ok(self.sens0.humidity as f64 / div),
-            1 => Ok(self.sens1.humidity as f64 / div),
-            2 => Ok(self.sens2.humidity as f64 / div),
-            3 => Ok(self.sens3.humidity as f64 / div),
-            _ => Err(PiXtendError::InvalidGpioInputIndex(index)),
-        }
+        self.humidity as f64 / div
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SensorKind {
     DHT11,
     DHT22,
+    /// Electrically equivalent to the DHT22 and uses the same scaling
+    AM2302,
+}
+
+impl SensorKind {
+    /// Temperature measurement range in Celsius according to the datasheet
+    fn temperature_range(&self) -> RangeInclusive<f64> {
+        match self {
+            SensorKind::DHT11 => 0.0..=50.0,
+            SensorKind::DHT22 | SensorKind::AM2302 => -40.0..=80.0,
+        }
+    }
+}
+
+/// Unit of a temperature reading
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    /// Converts the given temperature in Celsius to this unit
+    pub fn from_celsius(&self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+            TemperatureUnit::Kelvin => celsius + 273.15,
+        }
+    }
+}
+
+#[test]
+fn test_temperature_unit() {
+    // DHT22 with the sign bit set and -10.0°C
+    let data = [100, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let (_, sensor_in) = SensorIn::from_bytes((data.as_ref(), 0)).unwrap();
+    let celsius = sensor_in
+        .get_temperature_input(0, SensorKind::DHT22)
+        .unwrap();
+    assert_eq!(celsius, -10.0);
+    assert_eq!(TemperatureUnit::Celsius.from_celsius(celsius), -10.0);
+    assert_eq!(TemperatureUnit::Fahrenheit.from_celsius(celsius), 14.0);
+    assert_eq!(TemperatureUnit::Kelvin.from_celsius(celsius), 263.15);
+    assert_eq!(
+        sensor_in
+            .get_temperature_input(0, SensorKind::AM2302)
+            .unwrap(),
+        celsius
+    );
+}
+
+#[test]
+fn test_sensor_plausibility() {
+    // DHT22 with 81.0°C and 100.1% humidity, DHT11 with 51°C and 45% humidity
+    let data = [
+        0x2A, 0x03, 0xE9, 0x03, 0, 0x33, 0, 0x2D, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+    let (_, sensor_in) = SensorIn::from_bytes((data.as_ref(), 0)).unwrap();
+    assert!(matches!(
+        sensor_in.get_temperature_input(0, SensorKind::DHT22),
+        Err(PiXtendError::SensorReadImplausible { index: 0 })
+    ));
+    assert!(matches!(
+        sensor_in.get_humidity_input(0, SensorKind::DHT22),
+        Err(PiXtendError::SensorReadImplausible { index: 0 })
+    ));
+    assert!(matches!(
+        sensor_in.get_temperature_input(1, SensorKind::DHT11),
+        Err(PiXtendError::SensorReadImplausible { index: 1 })
+    ));
+    assert_eq!(
+        sensor_in.get_humidity_input(1, SensorKind::DHT11).unwrap(),
+        0.45
+    );
+    assert_eq!(
+        sensor_in
+            .get_temperature_input(2, SensorKind::DHT22)
+            .unwrap(),
+        0.0
+    );
+}
+
+#[test]
+fn test_sensor_from_values() {
+    let sensor = Sensor::from_values(SensorKind::DHT22, -10.5, 0.456);
+    assert_eq!(sensor.temperature, 0x8000 | 105);
+    assert_eq!(sensor.temperature(SensorKind::DHT22), -10.5);
+    assert_eq!(sensor.humidity(SensorKind::DHT22), 0.456);
+
+    let sensor = Sensor::from_values(SensorKind::DHT11, 21.0, 0.5);
+    assert_eq!(sensor.temperature(SensorKind::DHT11), 21.0);
+    assert_eq!(sensor.humidity(SensorKind::DHT11), 0.5);
+}
+
+#[test]
+fn test_dht11_humidity_scaling() {
+    // The DHT11 reports 45% in the high byte, which is 0.45 as a fraction
+    let data = [0, 0x19, 0, 0x2D, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    let (_, sensor_in) = SensorIn::from_bytes((data.as_ref(), 0)).unwrap();
+    assert_eq!(
+        sensor_in.get_humidity_input(0, SensorKind::DHT11).unwrap(),
+        0.45
+    );
+    assert_eq!(
+        sensor_in
+            .get_temperature_input(0, SensorKind::DHT11)
+            .unwrap(),
+        25.0
+    );
 }
@@ -1,14 +1,17 @@
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuWrite, DekuRead)]
 pub struct State {
     pub error_code: ErrorCode,
+    // Bits 1 to 3 are reserved
     #[deku(pad_bits_before = "3")]
     #[deku(bits = "1")]
     pub run: bool,
 }
 
-#[derive(Debug, DekuWrite, DekuRead, PartialEq, Eq)]
+/// Error code that the PiXtend board reports in the state of the input header
+#[derive(Debug, DekuWrite, DekuRead, PartialEq, Eq, Clone, Copy)]
 #[deku(id_type = "u8", bits = "4")]
 pub enum ErrorCode {
     #[deku(id = "0")]
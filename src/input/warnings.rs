@@ -1,7 +1,10 @@
+use alloc::vec::Vec;
 use deku::prelude::*;
 
-#[derive(Debug, DekuWrite, DekuRead, Clone, Copy)]
+/// Warnings that the PiXtend board reports in the input header, see `PiXtend::get_warnings`
+#[derive(Debug, DekuWrite, DekuRead, PartialEq, Eq, Clone, Copy, Default)]
 pub struct Warnings {
+    // Bits 0 and 4 to 7 are reserved
     #[deku(pad_bits_before = "4")]
     #[deku(bits = "1")]
     pub i2c_error: bool,
@@ -12,6 +15,15 @@ pub struct Warnings {
     pub retain_crc_error: bool,
 }
 
+impl Warnings {
+    /// Sets every warning that is set in the given warnings, keeping the already set ones
+    pub fn latch(&mut self, warnings: Warnings) {
+        self.i2c_error |= warnings.i2c_error;
+        self.voltage_error |= warnings.voltage_error;
+        self.retain_crc_error |= warnings.retain_crc_error;
+    }
+}
+
 #[test]
 fn test_warnings() {
     let data = [0b0000_1010];
@@ -28,3 +40,30 @@ fn test_warnings() {
     assert_eq!(warnings.retain_crc_error, false);
     assert_eq!(warnings.to_bytes().unwrap(), data);
 }
+
+#[test]
+fn test_warnings_reserved_bits() {
+    let data = [0b1111_0001];
+    let (_, warnings) = Warnings::from_bytes((data.as_ref(), 0)).unwrap();
+    assert_eq!(warnings.i2c_error, false);
+    assert_eq!(warnings.voltage_error, false);
+    assert_eq!(warnings.retain_crc_error, false);
+    assert_eq!(warnings.to_bytes().unwrap(), [0]);
+}
+
+#[test]
+fn test_warnings_latch() {
+    let mut latched = Warnings::default();
+    latched.latch(Warnings {
+        i2c_error: true,
+        ..Default::default()
+    });
+    latched.latch(Warnings {
+        voltage_error: true,
+        ..Default::default()
+    });
+    latched.latch(Warnings::default());
+    assert_eq!(latched.i2c_error, true);
+    assert_eq!(latched.voltage_error, true);
+    assert_eq!(latched.retain_crc_error, false);
+}
@@ -0,0 +1,12 @@
+use std::time::Instant;
+
+/// Change of a digital input between two cycles, see `PiXtend::input_events`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InputEvent {
+    /// Index of the digital input
+    pub index: u8,
+    /// Whether the input changed from low to high, otherwise it changed from high to low
+    pub rising: bool,
+    /// Completion time of the cycle in which the change was read
+    pub at: Instant,
+}
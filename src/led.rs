@@ -0,0 +1,16 @@
+use crate::ErrorCode;
+
+/// Meaning of the status LED "L1" of the PiXtend board, as derived from the last received state
+/// and the local LED configuration, see `PiXtend::led_meaning`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedState {
+    /// The LED is switched off via `PiXtend::set_led_disable`
+    Disabled,
+    /// The microcontroller is running and the communication is free of errors
+    Running,
+    /// The microcontroller is running, but reported an error for the last frame
+    Error(ErrorCode),
+    /// The microcontroller entered the safe state and the LED flashes depending on the cause,
+    /// a power cycle of the board is required
+    SafeState,
+}
@@ -0,0 +1,59 @@
+//! Internal logging macros, which forward to the `log` crate if the `log` feature is enabled and
+//! expand to nothing otherwise, so that logging is zero-cost when the feature is disabled.
+
+#[cfg(feature = "log")]
+macro_rules! log_trace {
+    ($($arg:tt)*) => { log::trace!($($arg)*) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! log_trace {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "log")]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { log::debug!($($arg)*) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "log")]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { log::warn!($($arg)*) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(feature = "log")]
+macro_rules! log_error {
+    ($($arg:tt)*) => { log::error!($($arg)*) };
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! log_error {
+    ($($arg:tt)*) => {};
+}
+
+/// Formats the given bytes as space separated hex values for the frame dumps
+#[cfg(feature = "log")]
+pub fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(feature = "log")]
+#[test]
+fn test_hex() {
+    assert_eq!(hex(&[0x00, 0x4B, 0xFF]), "00 4B FF");
+    assert_eq!(hex(&[]), "");
+}
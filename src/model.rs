@@ -0,0 +1,44 @@
+use alloc::vec::Vec;
+use deku::prelude::*;
+
+/// PiXtend V2 board model, which is sent in the output header and checked in the input header
+#[derive(Debug, DekuRead, DekuWrite, PartialEq, Eq, Default, Clone, Copy)]
+#[deku(id_type = "u8")]
+pub enum Model {
+    /// PiXtend V2 -L-
+    #[default]
+    #[deku(id = "b'L'")]
+    LModel,
+    /// PiXtend V2 -S-
+    #[deku(id = "b'S'")]
+    SModel,
+}
+
+impl Model {
+    /// Returns the model byte used in the header of the SPI frames
+    pub fn byte(&self) -> u8 {
+        match self {
+            Model::LModel => b'L',
+            Model::SModel => b'S',
+        }
+    }
+
+    /// Returns the length of a SPI frame for this model in bytes
+    pub fn frame_length(&self) -> usize {
+        match self {
+            Model::LModel => 111,
+            Model::SModel => 67,
+        }
+    }
+}
+
+#[test]
+fn test_model() {
+    let data = [b'S'];
+    let (_, model) = Model::from_bytes((data.as_ref(), 0)).unwrap();
+    assert_eq!(model, Model::SModel);
+    assert_eq!(model.to_bytes().unwrap(), data);
+
+    assert_eq!(Model::default().to_bytes().unwrap(), [b'L']);
+    assert_eq!(Model::LModel.byte(), b'L');
+}
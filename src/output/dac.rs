@@ -1,4 +1,5 @@
-use crate::Channel;
+use crate::{utils::round, Channel};
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default, Clone, Copy)]
@@ -12,20 +13,53 @@ pub struct Dac {
 }
 
 impl Dac {
-    pub fn new(channel: Channel, voltage: Option<f64>) -> Self {
-        let value = voltage.unwrap_or_default().clamp(0.0, 10.0);
-        let value = ((value / 10.0) * 1023.0) as u16;
-        let channel = match channel {
-            Channel::A => 0,
-            Channel::B => 1,
-        };
+    pub fn new(channel: Channel, voltage: f64) -> Self {
+        // Round to the nearest step, as truncating would bias the output voltage downwards
+        let value = voltage.clamp(0.0, 10.0);
+        let value = round((value / 10.0) * 1023.0) as u16;
 
         Self {
-            channel,
-            enabled: voltage.is_some(),
+            channel: Self::channel_bit(channel),
+            enabled: true,
             value,
         }
     }
+
+    pub fn from_raw(channel: Channel, value: u16) -> Self {
+        Self {
+            channel: Self::channel_bit(channel),
+            enabled: true,
+            value: value.min(1023),
+        }
+    }
+
+    pub fn disabled(channel: Channel) -> Self {
+        Self {
+            channel: Self::channel_bit(channel),
+            enabled: false,
+            value: 0,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn value(&self) -> u16 {
+        self.value
+    }
+
+    /// Returns the output voltage of the staged value or `None` if the DAC is disabled
+    pub fn voltage(&self) -> Option<f64> {
+        self.enabled.then(|| self.value as f64 * 10.0 / 1023.0)
+    }
+
+    fn channel_bit(channel: Channel) -> u8 {
+        match channel {
+            Channel::A => 0,
+            Channel::B => 1,
+        }
+    }
 }
 
 #[test]
@@ -44,3 +78,29 @@ fn test_dac() {
     assert_eq!(dac.value, 66);
     assert_eq!(dac.to_bytes().unwrap(), data);
 }
+
+#[test]
+fn test_dac_disabled() {
+    let dac = Dac::disabled(Channel::B);
+    assert_eq!(dac.channel, 1);
+    assert_eq!(dac.enabled, false);
+    assert_eq!(dac.value, 0);
+
+    let dac = Dac::from_raw(Channel::B, 2000);
+    assert_eq!(dac.channel, 1);
+    assert_eq!(dac.enabled, true);
+    assert_eq!(dac.value, 1023);
+
+    let dac = Dac::new(Channel::A, 12.0);
+    assert_eq!(dac.channel, 0);
+    assert_eq!(dac.enabled, true);
+    assert_eq!(dac.value, 1023);
+}
+
+#[test]
+fn test_dac_rounding() {
+    assert_eq!(Dac::new(Channel::A, 10.0).value, 1023);
+    assert_eq!(Dac::new(Channel::A, 5.0).value, 512);
+    assert_eq!(Dac::new(Channel::A, 0.0).value, 0);
+    assert_eq!(Dac::new(Channel::A, -1.0).value, 0);
+}
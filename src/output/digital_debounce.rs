@@ -1,4 +1,5 @@
 use crate::error::PiXtendError;
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -29,4 +30,17 @@ impl DigitalDebounce {
 
         Ok(())
     }
+
+    pub fn values(&self) -> [u8; 8] {
+        [
+            self.debounce01,
+            self.debounce23,
+            self.debounce45,
+            self.debounce67,
+            self.debounce89,
+            self.debounce1011,
+            self.debounce1213,
+            self.debounce1415,
+        ]
+    }
 }
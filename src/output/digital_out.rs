@@ -1,4 +1,5 @@
 use crate::error::PiXtendError;
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -50,6 +51,47 @@ impl DigitalOut {
 
         Ok(())
     }
+
+    pub fn get_digital_output(&self, index: u8) -> Result<bool, PiXtendError> {
+        match index {
+            0 => Ok(self.out0),
+            1 => Ok(self.out1),
+            2 => Ok(self.out2),
+            3 => Ok(self.out3),
+            4 => Ok(self.out4),
+            5 => Ok(self.out5),
+            6 => Ok(self.out6),
+            7 => Ok(self.out7),
+            8 => Ok(self.out8),
+            9 => Ok(self.out9),
+            10 => Ok(self.out10),
+            11 => Ok(self.out11),
+            _ => Err(PiXtendError::InvalidDigitalOutputIndex(index)),
+        }
+    }
+
+    /// Switches off all digital outputs
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn set_mask(&mut self, mask: u16) -> Result<(), PiXtendError> {
+        if mask > 0x0FFF {
+            return Err(PiXtendError::InvalidDigitalOutputMask(mask));
+        }
+
+        for index in 0..12 {
+            self.set_digital_output(index, mask & (1 << index) != 0)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn as_mask(&self) -> u16 {
+        (0..12)
+            .filter(|&index| self.get_digital_output(index).unwrap_or_default())
+            .fold(0, |mask, index| mask | (1 << index))
+    }
 }
 
 #[test]
@@ -72,3 +114,12 @@ fn test_digital_out_control() {
     let data = digital_out_control.to_bytes().unwrap();
     assert_eq!(data, [0b1010_1010, 0b0000_1010]);
 }
+
+#[test]
+fn test_digital_out_mask() {
+    let mut digital_out = DigitalOut::default();
+    digital_out.set_mask(0b1010_1010_1010).unwrap();
+    assert_eq!(digital_out.to_bytes().unwrap(), [0b1010_1010, 0b0000_1010]);
+    assert_eq!(digital_out.as_mask(), 0b1010_1010_1010);
+    assert!(digital_out.set_mask(0x1000).is_err());
+}
@@ -1,4 +1,5 @@
 use crate::{error::PiXtendError, GpioConfig};
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -1,4 +1,5 @@
 use crate::error::PiXtendError;
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -17,4 +18,8 @@ impl GpioDebounce {
 
         Ok(())
     }
+
+    pub fn values(&self) -> [u8; 2] {
+        [self.debounce01, self.debounce23]
+    }
 }
@@ -1,4 +1,5 @@
 use crate::{error::PiXtendError, GpioConfig};
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -45,6 +46,16 @@ impl GpioOut {
 
         Ok(())
     }
+
+    pub fn get_gpio_output(&self, index: u8) -> Result<bool, PiXtendError> {
+        match index {
+            0 => Ok(self.gpio0),
+            1 => Ok(self.gpio1),
+            2 => Ok(self.gpio2),
+            3 => Ok(self.gpio3),
+            _ => Err(PiXtendError::InvalidGpioOutputIndex(index)),
+        }
+    }
 }
 
 #[test]
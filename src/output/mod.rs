@@ -1,4 +1,5 @@
-use crate::utils::calc_crc16;
+use crate::{utils::calc_crc16, Model};
+use alloc::vec::Vec;
 use deku::prelude::*;
 use digital_debounce::DigitalDebounce;
 use digital_out::DigitalOut;
@@ -23,7 +24,7 @@ mod system;
 mod watchdog;
 
 pub use dac::Dac;
-pub use pwm::PwmPrescaler;
+pub use pwm::{PwmMode, PwmPrescaler};
 pub use watchdog::Watchdog;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -37,9 +38,9 @@ pub struct Output {
 }
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
-#[deku(magic = b"L")]
 pub struct Header {
-    #[deku(pad_bytes_before = "1")]
+    #[deku(pad_bytes_after = "1")]
+    pub model: Model,
     pub watchdog: Watchdog,
     #[deku(pad_bytes_after = "3")]
     pub system: System,
@@ -58,6 +59,11 @@ pub struct Data {
 }
 
 impl Output {
+    /// Returns the header and data CRCs, which are only up to date after `update`
+    pub fn crcs(&self) -> (u16, u16) {
+        (self.header_crc, self.data_crc)
+    }
+
     fn calculate_header_crc(&self) -> u16 {
         calc_crc16(self.header.to_bytes().into_iter().flatten())
     }
@@ -72,3 +78,11 @@ fn test_output() {
     let output = Output::default();
     assert_eq!(output.to_bytes().unwrap().len(), 111);
 }
+
+#[test]
+fn test_output_model() {
+    let mut output = Output::default();
+    assert_eq!(output.to_bytes().unwrap()[0], b'L');
+    output.header.model = Model::SModel;
+    assert_eq!(output.to_bytes().unwrap()[0], b'S');
+}
@@ -1,4 +1,5 @@
 use crate::{error::PiXtendError, Channel, PwmConfig};
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -31,6 +32,7 @@ pub struct PwmCtrl {
 }
 
 #[derive(Debug, DekuRead, DekuWrite, PartialEq, Eq, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[deku(id_type = "u8")]
 #[deku(bits = "3")]
 pub enum PwmPrescaler {
@@ -49,7 +51,8 @@ pub enum PwmPrescaler {
     Prescale15_625kHz,
 }
 
-#[derive(Debug, DekuRead, DekuWrite, PartialEq, Eq, Default)]
+/// Operating mode of a PWM output group
+#[derive(Debug, DekuRead, DekuWrite, PartialEq, Eq, Default, Clone, Copy)]
 #[deku(id_type = "u8")]
 #[deku(bits = "2")]
 pub enum PwmMode {
@@ -64,6 +67,31 @@ pub enum PwmMode {
     Frequency,
 }
 
+impl PwmPrescaler {
+    /// All active prescalers in descending order of their base frequency
+    pub(crate) const ACTIVE: [PwmPrescaler; 5] = [
+        PwmPrescaler::Prescale16MHz,
+        PwmPrescaler::Prescale2MHz,
+        PwmPrescaler::Prescale250kHz,
+        PwmPrescaler::Prescale62_5kHz,
+        PwmPrescaler::Prescale15_625kHz,
+    ];
+
+    /// Returns the base clock of the prescaler in Hz or `None` if the PWM is deactivated. The
+    /// resulting output frequency is `base_hz / 2 / value`, where the value is set via
+    /// `PwmConfig`.
+    pub fn base_hz(&self) -> Option<u32> {
+        match self {
+            PwmPrescaler::Deactivated => None,
+            PwmPrescaler::Prescale16MHz => Some(16_000_000),
+            PwmPrescaler::Prescale2MHz => Some(2_000_000),
+            PwmPrescaler::Prescale250kHz => Some(250_000),
+            PwmPrescaler::Prescale62_5kHz => Some(62_500),
+            PwmPrescaler::Prescale15_625kHz => Some(15_625),
+        }
+    }
+}
+
 impl Pwm {
     pub fn set_pwm_config(&mut self, index: u8, config: PwmConfig) -> Result<(), PiXtendError> {
         match index {
@@ -76,6 +104,14 @@ impl Pwm {
         Ok(())
     }
 
+    /// Sets the values of all channels to zero, keeping the mode and prescaler of the groups
+    pub fn clear(&mut self) {
+        for group in [&mut self.group0, &mut self.group1, &mut self.group2] {
+            group.channel0 = 0;
+            group.channel1 = 0;
+        }
+    }
+
     pub fn set_channel_value(
         &mut self,
         index: u8,
@@ -94,6 +130,18 @@ impl Pwm {
 
         Ok(())
     }
+
+    pub fn get_channel_value(&self, index: u8, channel: Channel) -> Result<u16, PiXtendError> {
+        match (index, channel) {
+            (0, Channel::A) => Ok(self.group0.channel0),
+            (0, Channel::B) => Ok(self.group0.channel1),
+            (1, Channel::A) => Ok(self.group1.channel0),
+            (1, Channel::B) => Ok(self.group1.channel1),
+            (2, Channel::A) => Ok(self.group2.channel0),
+            (2, Channel::B) => Ok(self.group2.channel1),
+            _ => Err(PiXtendError::InvalidPwmOutputGroupIndex(index)),
+        }
+    }
 }
 
 impl From<PwmConfig> for PwmGroup {
@@ -157,3 +205,10 @@ fn test_pwm_ctrl() {
     assert_eq!(pwm_ctrl.mode, PwmMode::Frequency);
     assert_eq!(pwm_ctrl.to_bytes().unwrap(), data);
 }
+
+#[test]
+fn test_prescaler_base_hz() {
+    assert_eq!(PwmPrescaler::Deactivated.base_hz(), None);
+    assert_eq!(PwmPrescaler::Prescale16MHz.base_hz(), Some(16_000_000));
+    assert_eq!(PwmPrescaler::Prescale15_625kHz.base_hz(), Some(15_625));
+}
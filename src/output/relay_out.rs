@@ -1,4 +1,5 @@
 use crate::error::PiXtendError;
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite, Default)]
@@ -26,6 +27,39 @@ impl RelayOut {
 
         Ok(())
     }
+
+    /// Switches off all relay outputs
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn get_relay_output(&self, index: u8) -> Result<bool, PiXtendError> {
+        match index {
+            0 => Ok(self.relay0),
+            1 => Ok(self.relay1),
+            2 => Ok(self.relay2),
+            3 => Ok(self.relay3),
+            _ => Err(PiXtendError::InvalidRelayOutputIndex(index)),
+        }
+    }
+
+    pub fn set_mask(&mut self, mask: u8) -> Result<(), PiXtendError> {
+        if mask > 0x0F {
+            return Err(PiXtendError::InvalidRelayOutputMask(mask));
+        }
+
+        for index in 0..4 {
+            self.set_relay_output(index, mask & (1 << index) != 0)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn as_mask(&self) -> u8 {
+        (0..4)
+            .filter(|&index| self.get_relay_output(index).unwrap_or_default())
+            .fold(0, |mask, index| mask | (1 << index))
+    }
 }
 
 #[test]
@@ -46,3 +80,12 @@ fn test_relay_out_control() {
     assert_eq!(relay_out.relay3, true);
     assert_eq!(relay_out.to_bytes().unwrap(), data);
 }
+
+#[test]
+fn test_relay_out_mask() {
+    let mut relay_out = RelayOut::default();
+    relay_out.set_mask(0b1001).unwrap();
+    assert_eq!(relay_out.to_bytes().unwrap(), [0b0000_1001]);
+    assert_eq!(relay_out.as_mask(), 0b1001);
+    assert!(relay_out.set_mask(0x10).is_err());
+}
@@ -1,17 +1,18 @@
-use crate::error::PiXtendError;
+use crate::{error::PiXtendError, RETAIN_SIZE};
+use alloc::vec::Vec;
 use deku::prelude::*;
 
 #[derive(Debug, DekuRead, DekuWrite)]
 pub struct Retain {
-    #[deku(count = "64")]
+    #[deku(count = "RETAIN_SIZE")]
     pub storage: Vec<u8>,
 }
 
 impl Retain {
     pub fn set_retain_data(&mut self, mut data: Vec<u8>) -> Result<(), PiXtendError> {
         match data.len() {
-            0..=64 => {
-                data.resize(64, 0);
+            0..=RETAIN_SIZE => {
+                data.resize(RETAIN_SIZE, 0);
                 self.storage = data;
             }
             _ => return Err(PiXtendError::InvalidRetainDataLength(data.len())),
@@ -19,12 +20,25 @@ impl Retain {
 
         Ok(())
     }
+
+    /// Copies the data into the existing storage and fills the remaining bytes with zeros
+    pub fn set_retain_slice(&mut self, data: &[u8]) -> Result<(), PiXtendError> {
+        if data.len() > RETAIN_SIZE {
+            return Err(PiXtendError::InvalidRetainDataLength(data.len()));
+        }
+
+        self.storage.clear();
+        self.storage.extend_from_slice(data);
+        self.storage.resize(RETAIN_SIZE, 0);
+
+        Ok(())
+    }
 }
 
 impl Default for Retain {
     fn default() -> Self {
         Self {
-            storage: vec![0; 64],
+            storage: vec![0; RETAIN_SIZE],
         }
     }
 }
@@ -38,3 +52,16 @@ fn test_retain() {
 
     assert_eq!(Retain::default().to_bytes().unwrap(), vec![0x00; 64]);
 }
+
+#[test]
+fn test_retain_slice() {
+    let mut retain = Retain::default();
+    retain.set_retain_slice(&[0xAA, 0xBB]).unwrap();
+    assert_eq!(retain.storage.len(), RETAIN_SIZE);
+    assert_eq!(retain.storage[..3], [0xAA, 0xBB, 0x00]);
+
+    assert!(matches!(
+        retain.set_retain_slice(&[0; 65]),
+        Err(PiXtendError::InvalidRetainDataLength(65))
+    ));
+}
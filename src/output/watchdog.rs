@@ -1,6 +1,10 @@
+use crate::error::PiXtendError;
+use alloc::vec::Vec;
+use core::time::Duration;
 use deku::prelude::*;
 
-#[derive(Debug, DekuRead, DekuWrite, PartialEq, Eq, Default)]
+#[derive(Debug, DekuRead, DekuWrite, PartialEq, Eq, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[deku(id_type = "u8")]
 pub enum Watchdog {
     #[default]
@@ -28,6 +32,107 @@ pub enum Watchdog {
     Activated8s,
 }
 
+/// All activated watchdog tiers in ascending order of their timeout
+const TIERS: [Watchdog; 10] = [
+    Watchdog::Activated16ms,
+    Watchdog::Activated32ms,
+    Watchdog::Activated64ms,
+    Watchdog::Activated0_125s,
+    Watchdog::Activated0_25s,
+    Watchdog::Activated0_5s,
+    Watchdog::Activated1s,
+    Watchdog::Activated2s,
+    Watchdog::Activated4s,
+    Watchdog::Activated8s,
+];
+
+impl Watchdog {
+    /// Returns the smallest watchdog tier with a timeout greater than or equal to the given
+    /// duration. Durations above 8s saturate to `Watchdog::Activated8s`, only a zero duration
+    /// returns `Watchdog::Deactivated`.
+    pub fn from_duration(duration: Duration) -> Watchdog {
+        if duration.is_zero() {
+            return Watchdog::Deactivated;
+        }
+
+        TIERS
+            .into_iter()
+            .find(|tier| {
+                tier.as_duration()
+                    .is_some_and(|timeout| timeout >= duration)
+            })
+            .unwrap_or(Watchdog::Activated8s)
+    }
+
+    /// Returns the timeout of the watchdog tier or `None` if the watchdog is deactivated.
+    pub fn as_duration(&self) -> Option<Duration> {
+        let millis = match self {
+            Watchdog::Deactivated => return None,
+            Watchdog::Activated16ms => 16,
+            Watchdog::Activated32ms => 32,
+            Watchdog::Activated64ms => 64,
+            Watchdog::Activated0_125s => 125,
+            Watchdog::Activated0_25s => 250,
+            Watchdog::Activated0_5s => 500,
+            Watchdog::Activated1s => 1000,
+            Watchdog::Activated2s => 2000,
+            Watchdog::Activated4s => 4000,
+            Watchdog::Activated8s => 8000,
+        };
+
+        Some(Duration::from_millis(millis))
+    }
+
+    /// Returns the numeric tier of the watchdog as sent to the PiXtend, where `0` is
+    /// `Watchdog::Deactivated` and `1` to `10` are the tiers from 16ms to 8s.
+    pub fn tier(&self) -> u8 {
+        TIERS
+            .iter()
+            .position(|tier| tier == self)
+            .map_or(0, |position| position as u8 + 1)
+    }
+}
+
+impl TryFrom<u8> for Watchdog {
+    type Error = PiXtendError;
+
+    /// Converts the numeric tier, see `Watchdog::tier`, i.e. as read from a configuration file.
+    /// Returns an error if the tier is greater than `10`.
+    fn try_from(tier: u8) -> Result<Self, Self::Error> {
+        match tier {
+            0 => Ok(Watchdog::Deactivated),
+            tier => TIERS
+                .get(tier as usize - 1)
+                .copied()
+                .ok_or(PiXtendError::InvalidWatchdogTier(tier)),
+        }
+    }
+}
+
+#[test]
+fn test_watchdog_duration() {
+    let from_millis = |millis| Watchdog::from_duration(Duration::from_millis(millis));
+    assert_eq!(
+        Watchdog::from_duration(Duration::ZERO),
+        Watchdog::Deactivated
+    );
+    assert_eq!(
+        Watchdog::from_duration(Duration::from_micros(1)),
+        Watchdog::Activated16ms
+    );
+    assert_eq!(from_millis(16), Watchdog::Activated16ms);
+    assert_eq!(from_millis(17), Watchdog::Activated32ms);
+    assert_eq!(from_millis(100), Watchdog::Activated0_125s);
+    assert_eq!(from_millis(1000), Watchdog::Activated1s);
+    assert_eq!(from_millis(60_000), Watchdog::Activated8s);
+
+    assert_eq!(Watchdog::Deactivated.as_duration(), None);
+    assert_eq!(
+        Watchdog::Activated0_25s.as_duration(),
+        Some(Duration::from_millis(250))
+    );
+}
+
 #[test]
 fn test_watchdog_control() {
     let data = [0];
@@ -54,3 +159,16 @@ fn test_watchdog_control() {
     let data = control.to_bytes().unwrap();
     assert_eq!(data, [4]);
 }
+
+#[test]
+fn test_watchdog_tier() {
+    for tier in 0..=10 {
+        let watchdog = Watchdog::try_from(tier).unwrap();
+        assert_eq!(watchdog.tier(), tier);
+        assert_eq!(watchdog.to_bytes().unwrap(), [tier]);
+    }
+    assert!(matches!(
+        Watchdog::try_from(11),
+        Err(PiXtendError::InvalidWatchdogTier(11))
+    ));
+}
@@ -0,0 +1,52 @@
+use crate::{Model, PwmMode, PwmPrescaler, Watchdog};
+
+/// Decoded values of all fields of the staged output frame and of the DAC words, see
+/// `PiXtend::output_debug`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputDebug {
+    pub model: Model,
+    pub watchdog: Watchdog,
+    pub safe: bool,
+    pub retain_copy: bool,
+    pub retain_enable: bool,
+    pub led_disable: bool,
+    pub gpio_pullup_enable: bool,
+    /// Digital debounce registers of the groups `0` to `7` in cycles
+    pub digital_debounce: [u8; 8],
+    /// Digital outputs, where bit N is the value of digital output N
+    pub digital_outputs: u16,
+    /// Relay outputs, where bit N is the value of relay output N
+    pub relay_outputs: u8,
+    /// GPIOs `0` to `3` that are configured as outputs in the GPIO control register
+    pub gpio_io: [bool; 4],
+    /// GPIOs `0` to `3` that are configured as sensors in the GPIO control register
+    pub gpio_sensors: [bool; 4],
+    /// GPIO output register, which also holds the pullup configuration of the GPIO inputs
+    pub gpio_outputs: [bool; 4],
+    /// GPIO debounce registers of the groups `0` and `1` in cycles
+    pub gpio_debounce: [u8; 2],
+    pub pwm: [PwmGroupDebug; 3],
+    pub dac: [DacDebug; 2],
+    pub retain: Vec<u8>,
+}
+
+/// Decoded registers of a PWM output group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PwmGroupDebug {
+    pub mode: PwmMode,
+    pub prescaler: PwmPrescaler,
+    pub channel_a_enabled: bool,
+    pub channel_b_enabled: bool,
+    /// Shared frequency value of the DutyCycle and Universal modes
+    pub frequency: u16,
+    pub channel_a: u16,
+    pub channel_b: u16,
+}
+
+/// Decoded word of a DAC channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DacDebug {
+    pub enabled: bool,
+    /// Raw 10 bit value of the channel
+    pub value: u16,
+}
@@ -0,0 +1,4615 @@
+#[cfg(feature = "embedded-hal")]
+use crate::DigitalOutputPin;
+use crate::{
+    analog_average::AnalogAverage,
+    analog_filter::AnalogFilter,
+    error::PiXtendError,
+    input::{self, ErrorCode, Input, ReferenceVoltage, SensorKind, TemperatureUnit, Warnings},
+    output::{Dac, Output, PwmPrescaler, Watchdog},
+    pwm_config,
+    recorder::Recorder,
+    sensor_smoothing::SensorSmoothing,
+    transport::lock,
+    Channel, CycleStats, DacDebug, DigitalOutputIndex, GpioConfig, GpioIndex, HardwareRevision,
+    InputEvent, InputSnapshot, LedState, OutputDebug, PiXtendBuilder, PiXtendConfig, PwmConfig,
+    PwmGroupDebug, PwmGroupIndex, RelayIndex, SpiTransport, Versions, RETAIN_SIZE,
+};
+#[cfg(test)]
+use crate::{Model, PwmMode};
+use deku::prelude::*;
+use rppal::{
+    gpio::{Gpio, OutputPin},
+    spi::{Bus, Mode, SlaveSelect, Spi},
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+pub(crate) const SPI_ENABLE_PIN: u8 = 24;
+pub(crate) const SPI_CLOCK_SPEED: u32 = 700_000;
+pub(crate) const COMMUNICATION_DELAY: Duration = Duration::from_millis(30);
+const DEFAULT_SERVO_RANGE: (f64, f64) = (0.0, 180.0);
+const SERVO_MAX_VALUE: u16 = 16000;
+/// Pulse width window in microseconds that the servo values `0` to `16000` are mapped onto
+const SERVO_PULSE_WINDOW_US: (u16, u16) = (1000, 2000);
+
+const DEFAULT_SENSOR_TIMEOUT_CYCLES: u32 = 3;
+const DEFAULT_SHORT_READ_RETRIES: u32 = 1;
+const DEFAULT_CURRENT_CALIBRATION: (f64, f64) = (input::CURRENT_SCALE, 0.0);
+/// Currents below this limit in mA indicate a broken wire of a 4mA to 20mA current loop
+const CURRENT_LOOP_BROKEN_MA: f64 = 3.5;
+
+pub struct PiXtend<T: SpiTransport = Spi> {
+    // The transports are shared with the blocking pool of `read_write_async`
+    spi_pixtend: Arc<Mutex<T>>,
+    spi_dac: Arc<Mutex<T>>,
+    input: Option<Input>,
+    input_bytes: Option<Vec<u8>>,
+    previous_digital_inputs: Option<u16>,
+    last_error_code: Option<ErrorCode>,
+    warning_latches: Warnings,
+    analog_average: AnalogAverage,
+    analog_filter: AnalogFilter,
+    pub(crate) output: Output,
+    gpio_configs: [GpioConfig; 4],
+    pwm_configs: [PwmConfig; 3],
+    servo_ranges: [(f64, f64); 3],
+    servo_pulse_widths: [(u16, u16); 3],
+    current_calibrations: [(f64, f64); 2],
+    reference_voltages: [ReferenceVoltage; 4],
+    sensor_empty_cycles: [u32; 4],
+    sensor_timeout_cycles: u32,
+    sensor_smoothing: SensorSmoothing,
+    dac_configs: [Dac; 2],
+    dac_enabled: bool,
+    cycle_delay: Duration,
+    stats: CycleStats,
+    enable_pin: Option<OutputPin>,
+    reset_on_drop: bool,
+    retain_copy_check: bool,
+    short_read_retries: u32,
+    stall_detection: Option<usize>,
+    identical_frames: usize,
+    recorder: Option<Recorder>,
+    skip_unchanged: bool,
+    cycle_skipped: bool,
+    last_payload: Option<Vec<u8>>,
+    last_read: Instant,
+    last_completion: Option<Instant>,
+    last_cycle_duration: Option<Duration>,
+}
+
+impl PiXtend {
+    /// Creates a new PiXtend instance communicating with the default SPI clock speed of 700kHz.
+    /// Use `PiXtend::builder` to apply settings before the first cycle.
+    pub fn new() -> Result<Self, PiXtendError> {
+        Self::with_clock_speed(SPI_CLOCK_SPEED)
+    }
+
+    /// Creates a new PiXtend instance communicating with the given SPI clock speed in Hz. The
+    /// same speed is used for the PiXtend and the DAC SPI.
+    ///
+    /// The default of 700kHz is the safe upper bound given by the PiXtend documentation. Higher
+    /// speeds may work with short cabling, but if the microcontroller can't keep up, `read_write`
+    /// returns `PiXtendError::SPIFrequencyTooHigh`. Lower speeds can help on noisy setups.
+    pub fn with_clock_speed(speed_hz: u32) -> Result<Self, PiXtendError> {
+        Self::with_spi(
+            speed_hz,
+            Bus::Spi0,
+            SlaveSelect::Ss0,
+            SlaveSelect::Ss1,
+            Some(SPI_ENABLE_PIN),
+        )
+    }
+
+    /// Creates a new PiXtend instance communicating over the given SPI bus, where the PiXtend
+    /// board and the DAC are selected with the given slave selects. If an enable pin is given,
+    /// it is driven high to enable the communication, otherwise no GPIO is touched.
+    pub(crate) fn with_spi(
+        speed_hz: u32,
+        bus: Bus,
+        pixtend_ss: SlaveSelect,
+        dac_ss: SlaveSelect,
+        enable_pin: Option<u8>,
+    ) -> Result<Self, PiXtendError> {
+        // Setting the enable pin to high enables the communication with the PiXtend board.
+        // The pin is kept high when the instance is dropped, unless reset on drop is enabled.
+        let enable_pin = match enable_pin {
+            Some(pin) => {
+                let mut enable_pin = Gpio::new()?.get(pin)?.into_output_high();
+                enable_pin.set_reset_on_drop(false);
+                Some(enable_pin)
+            }
+            None => None,
+        };
+
+        // Create the SPI instances for communication with the PiXtend board
+        let spi_pixtend = Spi::new(bus, pixtend_ss, speed_hz, Mode::Mode0)?;
+        let spi_dac = Spi::new(bus, dac_ss, speed_hz, Mode::Mode0)?;
+
+        let mut pixtend = Self::from_transports(spi_pixtend, spi_dac);
+        pixtend.enable_pin = enable_pin;
+        Ok(pixtend)
+    }
+
+    /// Returns a `PiXtendBuilder` to configure the PiXtend before the first cycle.
+    pub fn builder() -> PiXtendBuilder {
+        PiXtendBuilder::new()
+    }
+}
+
+impl<T: SpiTransport> PiXtend<T> {
+    /// Creates a new PiXtend instance communicating over the given transports for the PiXtend
+    /// board and the DAC. In contrast to `PiXtend::new`, no GPIO or SPI is initialized, so the
+    /// SPI enable pin is neither pulled high on creation nor low on drop. This allows using the
+    /// PiXtend without real hardware, i.e. to test an application with a simulated transport.
+    pub fn new_with_transport(spi_pixtend: T, spi_dac: T) -> Self {
+        Self::from_transports(spi_pixtend, spi_dac)
+    }
+
+    /// Creates a PiXtend instance with default configurations from the given transports
+    pub(crate) fn from_transports(spi_pixtend: T, spi_dac: T) -> Self {
+        // Create a default Output instance
+        let output = Output::default();
+
+        // Create default configurations
+        let gpio_configs = [GpioConfig::default(); 4];
+        let pwm_configs = [PwmConfig::default(); 3];
+        let dac_configs = [Dac::disabled(Channel::A), Dac::disabled(Channel::B)];
+
+        Self {
+            spi_pixtend: Arc::new(Mutex::new(spi_pixtend)),
+            spi_dac: Arc::new(Mutex::new(spi_dac)),
+            input: None,
+            input_bytes: None,
+            previous_digital_inputs: None,
+            last_error_code: None,
+            warning_latches: Warnings::default(),
+            analog_average: AnalogAverage::default(),
+            analog_filter: AnalogFilter::default(),
+            output,
+            gpio_configs,
+            pwm_configs,
+            servo_ranges: [DEFAULT_SERVO_RANGE; 3],
+            servo_pulse_widths: [SERVO_PULSE_WINDOW_US; 3],
+            current_calibrations: [DEFAULT_CURRENT_CALIBRATION; 2],
+            reference_voltages: [ReferenceVoltage::V10; 4],
+            sensor_empty_cycles: [0; 4],
+            sensor_timeout_cycles: DEFAULT_SENSOR_TIMEOUT_CYCLES,
+            sensor_smoothing: SensorSmoothing::default(),
+            dac_configs,
+            dac_enabled: true,
+            cycle_delay: COMMUNICATION_DELAY,
+            stats: CycleStats::default(),
+            enable_pin: None,
+            reset_on_drop: false,
+            retain_copy_check: true,
+            short_read_retries: DEFAULT_SHORT_READ_RETRIES,
+            stall_detection: None,
+            identical_frames: 0,
+            recorder: None,
+            skip_unchanged: false,
+            cycle_skipped: false,
+            last_payload: None,
+            last_read: Instant::now(),
+            last_completion: None,
+            last_cycle_duration: None,
+        }
+    }
+
+    /// If the watchdog is activated, the communication between the Raspberry Pi and the PiXtend
+    /// is monitored. If there is a pause between two valid cycles which is longer than the
+    /// set time, the watchdog becomes active and puts the microcontroller into a safe state.
+    /// An invalid cycle (e.g. due to a CRC error) is evaluated by the watchdog as if no
+    /// cycle had been performed. Use `Watchdog::from_duration` to select the tier for a timeout.
+    pub fn set_watchdog(&mut self, watchdog: Watchdog) {
+        self.output.header.watchdog = watchdog;
+    }
+
+    /// Returns the currently staged watchdog setting, see `set_watchdog`.
+    pub fn get_watchdog(&self) -> Watchdog {
+        self.output.header.watchdog
+    }
+
+    /// The Retain Copy option can be used to configure which data is visible in the retain
+    /// input area. At the start value `0`, the last saved data is transferred from the
+    /// microcontroller to the Raspberry Pi, normal Retain operation. If the value `1` is set for
+    /// the RC bit, the last data sent by the Raspberry Pi is returned. In the retain area, the
+    /// retain data is mirrored, but with a cycle delay. The content of the retain area is
+    /// not lost, it is just not displayed as long as the RC bit is `1`.
+    pub fn set_retain_copy(&mut self, value: bool) {
+        self.output.header.system.retain_copy = value;
+    }
+
+    /// With the retain enable bit, the retain function of the PiXtend can be activated. For this
+    /// purpose, a `1` is written in to this bit. By default, RE is `0`, after a reset or power-up
+    /// and therefore retain is deactivated.
+    pub fn set_retain_enable(&mut self, value: bool) {
+        self.output.header.system.retain_enable = value;
+    }
+
+    /// Configures whether `validate_config` rejects a retain copy without the retain function
+    /// being enabled. The PiXtend tolerates this combination, but it usually is a configuration
+    /// mistake, since the retain copy has no effect then. The default is `true`.
+    pub fn set_retain_copy_check(&mut self, value: bool) {
+        self.retain_copy_check = value;
+    }
+
+    /// Returns whether the retain function is currently enabled, see `set_retain_enable`.
+    pub fn get_retain_enable(&self) -> bool {
+        self.output.header.system.retain_enable
+    }
+
+    /// Setting this option to `true` disables the status LED. The LED is active by default.
+    pub fn set_led_disable(&mut self, value: bool) {
+        self.output.header.system.led_disable = value;
+    }
+
+    /// Returns whether the status LED is currently disabled, see `set_led_disable`.
+    pub fn get_led_disable(&self) -> bool {
+        self.output.header.system.led_disable
+    }
+
+    /// The gpio pullup enable bit can be used to enable the pull-up resistors of the PiXtend
+    /// GPIOS, but they are only activated, when the GPIOs are configured as inputs and have the
+    /// pullup option set as well.
+    pub fn set_gpio_pullup_enable(&mut self, value: bool) {
+        self.output.header.system.gpio_pullup_enable = value;
+    }
+
+    /// Returns whether the GPIO pullup resistors are currently enabled globally, see
+    /// `set_gpio_pullup_enable`.
+    pub fn get_gpio_pullup_enable(&self) -> bool {
+        self.output.header.system.gpio_pullup_enable
+    }
+
+    /// Puts the microcontroller into the safe state defined as follows:
+    /// - All digital outputs and relays are switched off / put into idle state
+    /// - PWM outputs are switched to high impedance (tri-state)
+    /// - Retain data is stored when retain option has been activated
+    /// - The status LED "L1" flashes depending on the cause of the error
+    /// - The microcontroller or the PiXtend device has to be restarted (power cycle)
+    pub fn enable_safe_mode(&mut self) {
+        self.output.header.system.safe = true;
+    }
+
+    /// Sets the minimum delay between two cycles, which `read_write` and `write` enforce by
+    /// sleeping if the last transfer was less than the delay ago. The PiXtend documentation
+    /// recommends a minimum of 30ms, which is also the default. Shorter delays risk CRC and
+    /// timing errors and should only be used if they have been validated with the firmware of the
+    /// connected board.
+    pub fn set_cycle_delay(&mut self, delay: Duration) {
+        self.cycle_delay = delay;
+    }
+
+    /// Returns the minimum delay between two cycles, see `set_cycle_delay`.
+    pub fn get_cycle_delay(&self) -> Duration {
+        self.cycle_delay
+    }
+
+    /// Configures whether the PiXtend board is put into the safe state when the instance is
+    /// dropped. If enabled, a final frame with the safe state request is sent on drop, which
+    /// switches off all digital outputs and relays, and the SPI enable pin is pulled low. Since
+    /// the cycle delay is respected, dropping the instance may block for up to the cycle delay.
+    /// Any errors of the final frame are ignored. The default is `false`, so the outputs latch
+    /// their last state.
+    pub fn set_reset_on_drop(&mut self, value: bool) {
+        self.reset_on_drop = value;
+    }
+
+    /// Configures whether `read_write`, `try_read_write` and `write` skip the SPI transfer if the
+    /// outputs didn't change since the last transmitted frame. The CRC fields are excluded from
+    /// the comparison, since they are derived from the other fields, while the DAC values are
+    /// included. Skipped cycles still wait for the cycle delay, but they don't refresh the input
+    /// data, see `last_cycle_skipped`, and `try_read_write` returns `Ok(false)` in that case.
+    /// `flush` always transmits. The default is `false`.
+    ///
+    /// This is not safe with the watchdog enabled, since the PiXtend board treats the missing
+    /// cycles as a communication fault and enters the safe state, see `set_watchdog`.
+    pub fn set_skip_unchanged(&mut self, value: bool) {
+        self.skip_unchanged = value;
+    }
+
+    /// Returns whether the last cycle was skipped because the outputs didn't change, see
+    /// `set_skip_unchanged`. In that case the input data is from an earlier transfer and may be
+    /// stale.
+    pub fn last_cycle_skipped(&self) -> bool {
+        self.cycle_skipped
+    }
+
+    /// Sets how often a transfer is repeated within one cycle if the PiXtend board returns fewer
+    /// bytes than a full frame, before `InvalidSpiResponseLength` is returned. Each retry waits
+    /// for the cycle delay and is counted as a separate cycle in the statistics. The default is
+    /// one retry, `0` disables the retries. `read_write_retry` ignores this setting and counts
+    /// short reads as its own attempts.
+    pub fn set_short_read_retries(&mut self, retries: u32) {
+        self.short_read_retries = retries;
+    }
+
+    /// Clears the staged safe state request set via `enable_safe_mode`, so that it is no longer
+    /// sent in the next cycle. This only affects the output frame: if the microcontroller
+    /// actually entered the safe state (i.e. through the watchdog or a previous cycle with the
+    /// safe bit set), a power cycle of the PiXtend board is still required.
+    pub fn clear_safe_mode(&mut self) {
+        self.output.header.system.safe = false;
+    }
+
+    /// Returns whether the safe state request is staged in the output frame, see
+    /// `enable_safe_mode`. This reflects the local configuration only, use `is_running` to check
+    /// the state reported by the microcontroller.
+    pub fn is_in_safe_mode(&self) -> bool {
+        self.output.header.system.safe
+    }
+
+    /// Configures the digital debounce for the given group. There are 8 groups of two digital
+    /// inputs each available:
+    /// - Group 0: Digital input 0 and 1
+    /// - Group 1: Digital input 2 and 3
+    /// - Group 2: Digital input 4 and 5
+    /// - Group 3: Digital input 6 and 7
+    /// - Group 4: Digital input 8 and 9
+    /// - Group 5: Digital input 10 and 11
+    /// - Group 6: Digital input 12 and 13
+    /// - Group 7: Digital input 14 and 15
+    ///
+    /// The debounce time is set in cycles. Each cycle lasts the cycle delay, 30ms by default,
+    /// see `set_cycle_delay`.
+    pub fn set_digital_debounce(&mut self, group: u8, value: u8) -> Result<(), PiXtendError> {
+        self.output
+            .data
+            .digital_debounce
+            .set_digital_debounce(group, value)
+    }
+
+    /// Configures the digital debounce for the given group in milliseconds, which are converted
+    /// to cycles of the current cycle delay and rounded down, see `set_digital_debounce`. The
+    /// conversion is not repeated if the cycle delay is changed afterwards.
+    /// Returns an error if the group is invalid, if the cycle delay is zero or if the time
+    /// exceeds 255 cycles (7650ms with the default cycle delay of 30ms).
+    pub fn set_digital_debounce_ms(&mut self, group: u8, ms: u32) -> Result<(), PiXtendError> {
+        let cycles = self.debounce_cycles(ms)?;
+        self.set_digital_debounce(group, cycles)
+    }
+
+    /// Writes the given value to the digital output with the given index.
+    /// Valid indexes are `0` to `11`, returns an error if the index is invalid.
+    pub fn set_digital_output(&mut self, index: u8, value: bool) -> Result<(), PiXtendError> {
+        self.output
+            .data
+            .digital_out
+            .set_digital_output(index, value)
+    }
+
+    /// Reads back the currently staged value of the digital output with the given index.
+    /// Valid indexes are `0` to `11`, returns an error if the index is invalid.
+    pub fn get_digital_output(&self, index: u8) -> Result<bool, PiXtendError> {
+        self.output.data.digital_out.get_digital_output(index)
+    }
+
+    /// Sets the digital output at the given index, which is checked to be in range when it is
+    /// constructed, see `DigitalOutputIndex::new` and `set_digital_output`.
+    pub fn set_digital_output_at(
+        &mut self,
+        index: DigitalOutputIndex,
+        value: bool,
+    ) -> Result<(), PiXtendError> {
+        self.set_digital_output(index.get(), value)
+    }
+
+    /// Returns an adapter for the digital output with the given index, which implements the
+    /// `OutputPin` trait of `embedded-hal`, i.e. to reuse existing drivers. The adapter borrows
+    /// the PiXtend, and like with `set_digital_output` the value only takes effect with the next
+    /// `read_write` or `write`.
+    /// Valid indexes are `0` to `11`, returns an error if the index is invalid.
+    #[cfg(feature = "embedded-hal")]
+    pub fn digital_output_pin(
+        &mut self,
+        index: u8,
+    ) -> Result<DigitalOutputPin<'_, T>, PiXtendError> {
+        if index > 11 {
+            return Err(PiXtendError::InvalidDigitalOutputIndex(index));
+        }
+
+        Ok(DigitalOutputPin::new(self, index))
+    }
+
+    /// Writes all digital outputs at once, where bit N of the mask is the value of digital
+    /// output N. Returns an error if any of the bits 12 to 15 are set.
+    pub fn set_digital_outputs_mask(&mut self, mask: u16) -> Result<(), PiXtendError> {
+        self.output.data.digital_out.set_mask(mask)
+    }
+
+    /// Writes the given value to the relay output with the given index.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    pub fn set_relay_output(&mut self, index: u8, value: bool) -> Result<(), PiXtendError> {
+        self.output.data.relay_out.set_relay_output(index, value)
+    }
+
+    /// Reads back the currently staged value of the relay output with the given index.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    pub fn get_relay_output(&self, index: u8) -> Result<bool, PiXtendError> {
+        self.output.data.relay_out.get_relay_output(index)
+    }
+
+    /// Sets the relay output at the given index, which is checked to be in range when it is
+    /// constructed, see `RelayIndex::new` and `set_relay_output`.
+    pub fn set_relay_output_at(
+        &mut self,
+        index: RelayIndex,
+        value: bool,
+    ) -> Result<(), PiXtendError> {
+        self.set_relay_output(index.get(), value)
+    }
+
+    /// Writes all relay outputs at once, where bit N of the mask is the value of relay output N.
+    /// Returns an error if any of the bits 4 to 7 are set.
+    pub fn set_relays_mask(&mut self, mask: u8) -> Result<(), PiXtendError> {
+        self.output.data.relay_out.set_mask(mask)
+    }
+
+    /// Reads back the currently staged values of all relay outputs, where bit N of the returned
+    /// mask is the value of relay output N.
+    pub fn get_relays_mask(&self) -> u8 {
+        self.output.data.relay_out.as_mask()
+    }
+
+    /// Configures the GPIO with the given index. The configuration can be one of the following:
+    /// - `GpioConfig::Output`: The GPIO is configured as an output
+    /// - `GpioConfig::Input(false)`: The GPIO is configured as an input without a pull-up
+    ///   resistor
+    /// - `GpioConfig::Input(true)`: The GPIO is configured as an input with a pull-up resistor
+    /// - `GpioConfig::Sensor`: The GPIO is configured as a onewire sensor input, for example
+    ///   for a DHT11, DHT22 or AM2302 sensor
+    ///
+    /// Returns an error in the following cases:
+    /// - Index not in the valid range of `0` to `3`
+    /// - Trying to configure a GPIO sensor input while a PWM output is already configured
+    /// - Trying to configure a GPIO pullup resistor without first enabling it globally via
+    ///   `set_gpio_pullup_enable`
+    pub fn set_gpio_config(&mut self, index: u8, config: GpioConfig) -> Result<(), PiXtendError> {
+        // Check if a PWM output is configured at the same time
+        if config == GpioConfig::Sensor
+            && self
+                .pwm_configs
+                .iter()
+                .any(|config| *config != PwmConfig::Deactivated)
+        {
+            return Err(PiXtendError::PwmAndDhtExclusive);
+        }
+
+        // To enable a pullup resistor on an input GPIO, the GPIO pullup enable bit must be set
+        if config == GpioConfig::Input(true) && !self.output.header.system.gpio_pullup_enable {
+            return Err(PiXtendError::GpioPullupNotGloballyEnabled);
+        }
+
+        self.output.data.gpio_ctrl.set_gpio_config(index, config)?;
+        self.output.data.gpio_out.set_gpio_config(index, config)?;
+        *self
+            .gpio_configs
+            .get_mut(index as usize)
+            .ok_or(PiXtendError::InvalidGpioOutputIndex(index))? = config;
+
+        Ok(())
+    }
+
+    /// Configures the GPIO at the given checked index, see `set_gpio_config`.
+    /// Returns the same errors as `set_gpio_config`, except for an invalid index.
+    pub fn set_gpio_config_at(
+        &mut self,
+        index: GpioIndex,
+        config: GpioConfig,
+    ) -> Result<(), PiXtendError> {
+        self.set_gpio_config(index.get(), config)
+    }
+
+    /// Configures all four GPIOs at once, where the configuration at index `0` is applied to
+    /// GPIO 0 and so on, see `set_gpio_config`. The configurations are checked as a whole before
+    /// any of them is applied, so an error leaves the current configuration untouched.
+    /// Returns the same errors as `set_gpio_config`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pixtend::{PiXtend, GpioConfig};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend
+    ///     .set_gpio_configs([
+    ///         GpioConfig::Output,
+    ///         GpioConfig::Output,
+    ///         GpioConfig::Input(false),
+    ///         GpioConfig::Sensor,
+    ///     ])
+    ///     .unwrap();
+    /// ```
+    pub fn set_gpio_configs(&mut self, configs: [GpioConfig; 4]) -> Result<(), PiXtendError> {
+        // Check if a PWM output is configured at the same time as any sensor
+        if configs.contains(&GpioConfig::Sensor)
+            && self
+                .pwm_configs
+                .iter()
+                .any(|config| *config != PwmConfig::Deactivated)
+        {
+            return Err(PiXtendError::PwmAndDhtExclusive);
+        }
+
+        // To enable a pullup resistor on an input GPIO, the GPIO pullup enable bit must be set
+        if configs.contains(&GpioConfig::Input(true))
+            && !self.output.header.system.gpio_pullup_enable
+        {
+            return Err(PiXtendError::GpioPullupNotGloballyEnabled);
+        }
+
+        for (index, config) in (0..).zip(configs) {
+            self.set_gpio_config(index, config)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the given value to the GPIO output with the given index.
+    /// Returns an error if the given index is invalid (0 to 3) or if the GPIO is not configured
+    /// as an output, where a GPIO configured as a sensor results in
+    /// `PiXtendError::GpioConfiguredAsSensor`.
+    pub fn set_gpio_output(&mut self, index: u8, value: bool) -> Result<(), PiXtendError> {
+        // Check if the given index is valid
+        if index > 3 {
+            return Err(PiXtendError::InvalidGpioOutputIndex(index));
+        }
+
+        // Check if the GPIO is configured as an output
+        match self.gpio_configs[index as usize] {
+            GpioConfig::Output => {}
+            GpioConfig::Sensor => return Err(PiXtendError::GpioConfiguredAsSensor(index)),
+            GpioConfig::Input(_) => return Err(PiXtendError::GpioNotConfiguredAsOutput(index)),
+        }
+
+        self.output.data.gpio_out.set_gpio_output(index, value)
+    }
+
+    /// Reads back the currently staged value of the GPIO output with the given index.
+    /// Returns the same errors as `set_gpio_output`.
+    pub fn get_gpio_output(&self, index: u8) -> Result<bool, PiXtendError> {
+        // Check if the given index is valid
+        if index > 3 {
+            return Err(PiXtendError::InvalidGpioOutputIndex(index));
+        }
+
+        // Check if the GPIO is configured as an output
+        match self.gpio_configs[index as usize] {
+            GpioConfig::Output => {}
+            GpioConfig::Sensor => return Err(PiXtendError::GpioConfiguredAsSensor(index)),
+            GpioConfig::Input(_) => return Err(PiXtendError::GpioNotConfiguredAsOutput(index)),
+        }
+
+        self.output.data.gpio_out.get_gpio_output(index)
+    }
+
+    /// Configures the gpio debounce for the given group. There are 2 groups of two digital
+    /// inputs each available:
+    /// - Group 0: Digital input 0 and 1
+    /// - Group 1: Digital input 2 and 3
+    ///
+    /// The debounce time is set in cycles. Each cycle lasts the cycle delay, 30ms by default,
+    /// see `set_cycle_delay`.
+    pub fn set_gpio_debounce(&mut self, group: u8, value: u8) -> Result<(), PiXtendError> {
+        self.output
+            .data
+            .gpio_debounce
+            .set_gpio_debounce(group, value)
+    }
+
+    /// Configures the gpio debounce for the given group in milliseconds, which are converted to
+    /// cycles of the current cycle delay and rounded down, see `set_gpio_debounce`. The
+    /// conversion is not repeated if the cycle delay is changed afterwards.
+    /// Returns an error if the group is invalid, if the cycle delay is zero or if the time
+    /// exceeds 255 cycles (7650ms with the default cycle delay of 30ms).
+    pub fn set_gpio_debounce_ms(&mut self, group: u8, ms: u32) -> Result<(), PiXtendError> {
+        let cycles = self.debounce_cycles(ms)?;
+        self.set_gpio_debounce(group, cycles)
+    }
+
+    /// Configures the PWM output for the group with the given index. Each group has two channels
+    /// (A and B). The configuration can be one of the following:
+    /// - `PwmConfig::Deactivated`: The PWM output is deactivated
+    /// - `PwmConfig::Servo`: The PWM output is configured for servos with a frequency of 50Hz
+    /// - `PwmConfig::DutyCycle`: The PWM output group can set individual duty cycles for channel A
+    ///   and B, but they share the same frequency, which is set via the prescaler and frequency
+    /// - `PwmConfig::Universal`: The PWM output group can only configure frequency and duty
+    ///   cycle of channel A, while channel B always has 50% duty cycle and half the frequency of A
+    /// - `PwmConfig::Frequency`: The PWM output group can set individual frequencies for channel
+    ///   A and B, but they both have a duty cycle of 50%
+    ///
+    /// Valid indexes are `0` to `2`, returns an error if the index is invalid.
+    pub fn set_pwm_config(&mut self, index: u8, config: PwmConfig) -> Result<(), PiXtendError> {
+        // Check if any DHT sensors are configured, which is not allowed
+        if config != PwmConfig::Deactivated && self.gpio_configs.contains(&GpioConfig::Sensor) {
+            return Err(PiXtendError::PwmAndDhtExclusive);
+        }
+
+        // Set the PWM configuration
+        self.output.data.pwm.set_pwm_config(index, config)?;
+        *self
+            .pwm_configs
+            .get_mut(index as usize)
+            .ok_or(PiXtendError::InvalidPwmOutputGroupIndex(index))? = config;
+
+        Ok(())
+    }
+
+    /// Configures the PWM group at the given checked index, see `set_pwm_config`.
+    /// Returns the same errors as `set_pwm_config`, except for an invalid index.
+    pub fn set_pwm_config_at(
+        &mut self,
+        index: PwmGroupIndex,
+        config: PwmConfig,
+    ) -> Result<(), PiXtendError> {
+        self.set_pwm_config(index.get(), config)
+    }
+
+    /// Sets the PWM servo position for the given index and channel as a value between `0` and
+    /// `16000`. The value is linearly mapped to the pulse width between `1ms` and `2ms`, where
+    /// 1ms is the minimum position and 2ms is the maximum position. The frequency is always 50Hz.
+    /// Returns an error if the given index is invalid (0 to 2), if the PWM is not configured
+    /// as a servo or if the value is greater than `16000`.
+    ///
+    /// # Example
+    /// We want to set the servo position of PWM 0A to half of the maximum position:
+    /// ```no_run
+    /// # use pixtend::{PiXtend, PwmConfig, Channel};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend.set_pwm_config(0, PwmConfig::Servo { channel_a: true, channel_b: true });
+    /// pixtend.set_pwm_servo(0, Channel::A, 8000).unwrap();
+    /// ```
+    pub fn set_pwm_servo(
+        &mut self,
+        index: u8,
+        channel: Channel,
+        value: u16,
+    ) -> Result<(), PiXtendError> {
+        // Check if the given index is valid
+        if index > 2 {
+            return Err(PiXtendError::InvalidPwmOutputGroupIndex(index));
+        }
+
+        // Check if the pwm is configured as a servo
+        if !matches!(self.pwm_configs[index as usize], PwmConfig::Servo { .. }) {
+            return Err(PiXtendError::PwmNotConfiguredAsServo(index));
+        }
+
+        // Check if the value results in a valid pulse width
+        if value > SERVO_MAX_VALUE {
+            return Err(PiXtendError::PwmServoValueOutOfRange { index, value });
+        }
+
+        self.stage_pwm_value(index, channel, value)
+    }
+
+    /// Sets the PWM servo position for the given index and channel as an angle in degrees. The
+    /// angle is clamped to the servo range of the group, `0°` to `180°` by default, and linearly
+    /// mapped onto the pulse widths of the servo calibration, `1000µs` to `2000µs` by default,
+    /// which are converted to the values `0` to `16000` of `set_pwm_servo`.
+    /// Returns the same errors as `set_pwm_servo`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pixtend::{PiXtend, PwmConfig, Channel};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend.set_pwm_config(0, PwmConfig::Servo { channel_a: true, channel_b: true });
+    /// pixtend.set_pwm_servo_angle(0, Channel::A, 90.0).unwrap();
+    /// ```
+    pub fn set_pwm_servo_angle(
+        &mut self,
+        index: u8,
+        channel: Channel,
+        degrees: f64,
+    ) -> Result<(), PiXtendError> {
+        let (min, max) = *self
+            .servo_ranges
+            .get(index as usize)
+            .ok_or(PiXtendError::InvalidPwmOutputGroupIndex(index))?;
+
+        let (min_us, max_us) = self.servo_pulse_widths[index as usize];
+
+        let position = (degrees.clamp(min, max) - min) / (max - min);
+        let pulse_us = min_us as f64 + position * (max_us - min_us) as f64;
+        let (window_min_us, window_max_us) = SERVO_PULSE_WINDOW_US;
+        let window = (pulse_us - window_min_us as f64) / (window_max_us - window_min_us) as f64;
+        let value = (window * SERVO_MAX_VALUE as f64).round() as u16;
+        self.set_pwm_servo(index, channel, value)
+    }
+
+    /// Sets the calibration of the servos of the given PWM group, which `set_pwm_servo_angle`
+    /// uses to map `0°` to `max_degrees` linearly onto the pulse widths `min_us` to `max_us`.
+    /// The pulse widths must lie within the `1000µs` to `2000µs` window of `set_pwm_servo`. This
+    /// also sets the servo range of the group to `0°` to `max_degrees`, see `set_servo_range`.
+    /// The default is `1000µs` to `2000µs` over `0°` to `180°`.
+    /// Returns an error if the index is invalid (0 to 2), if the pulse widths are outside of the
+    /// window or `min_us` is not smaller than `max_us`, or if `max_degrees` is not positive.
+    ///
+    /// # Example
+    /// A 270° servo that reaches its endpoints at 1100µs and 1900µs:
+    /// ```no_run
+    /// # use pixtend::{PiXtend, PwmConfig, Channel};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend
+    ///     .set_pwm_config(0, PwmConfig::Servo { channel_a: true, channel_b: true })
+    ///     .unwrap();
+    /// pixtend.set_servo_calibration(0, 1100, 1900, 270.0).unwrap();
+    /// pixtend.set_pwm_servo_angle(0, Channel::A, 135.0).unwrap();
+    /// ```
+    pub fn set_servo_calibration(
+        &mut self,
+        index: u8,
+        min_us: u16,
+        max_us: u16,
+        max_degrees: f64,
+    ) -> Result<(), PiXtendError> {
+        // Check if the given index is valid
+        if index > 2 {
+            return Err(PiXtendError::InvalidPwmOutputGroupIndex(index));
+        }
+
+        let (window_min_us, window_max_us) = SERVO_PULSE_WINDOW_US;
+        if !(window_min_us <= min_us && min_us < max_us && max_us <= window_max_us) {
+            return Err(PiXtendError::InvalidServoCalibration(index));
+        }
+
+        self.set_servo_range(index, 0.0, max_degrees)?;
+        self.servo_pulse_widths[index as usize] = (min_us, max_us);
+        Ok(())
+    }
+
+    /// Sets the angle range in degrees that `set_pwm_servo_angle` maps onto the full servo
+    /// pulse width range of the given PWM group. The default range is `0°` to `180°`.
+    /// Returns an error if the index is invalid (0 to 2) or if the minimum is not smaller than
+    /// the maximum or not finite.
+    pub fn set_servo_range(
+        &mut self,
+        index: u8,
+        min_degrees: f64,
+        max_degrees: f64,
+    ) -> Result<(), PiXtendError> {
+        if !(min_degrees.is_finite() && max_degrees.is_finite() && min_degrees < max_degrees) {
+            return Err(PiXtendError::InvalidServoRange(index));
+        }
+
+        *self
+            .servo_ranges
+            .get_mut(index as usize)
+            .ok_or(PiXtendError::InvalidPwmOutputGroupIndex(index))? = (min_degrees, max_degrees);
+
+        Ok(())
+    }
+
+    /// Sets the PWM duty cycle for the given index and channel as a value between `0` and
+    /// the configured `frequency`, where `0` is 0% duty cycle and the configured frequency is
+    /// 100% duty cycle.
+    /// Returns an error if the given index is invalid (0 to 2) or if the PWM is not configured
+    /// for DutyCycleMode or if the configured prescaler is deactivated or the configured
+    /// frequency exceeds `20kHz`. Setting channel B in Universal mode returns
+    /// `PiXtendError::PwmChannelNotSettable`, since only channel A is configurable there.
+    ///
+    /// # Example
+    /// We want to set the duty cycle of PWM 0A to `50%` with 1 Hz:
+    /// ```no_run
+    /// # use pixtend::{PiXtend, PwmConfig, Channel, PwmPrescaler};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend.set_pwm_config(0, PwmConfig::DutyCycle {
+    ///    prescaler: PwmPrescaler::Prescale62_5kHz,
+    ///    frequency: 31250,
+    ///    channel_a: true,
+    ///    channel_b: true,
+    /// });
+    /// pixtend.set_pwm_duty_cycle(0, Channel::A, 15625).unwrap();
+    /// ```
+    pub fn set_pwm_duty_cycle(
+        &mut self,
+        index: u8,
+        channel: Channel,
+        value: u16,
+    ) -> Result<(), PiXtendError> {
+        // Check if the given index is valid
+        if index > 2 {
+            return Err(PiXtendError::InvalidPwmOutputGroupIndex(index));
+        }
+
+        // The duty cycle is only configurable in DutyCycle and Universal mode
+        if !matches!(
+            self.pwm_configs[index as usize],
+            PwmConfig::DutyCycle { .. } | PwmConfig::Universal { .. }
+        ) {
+            return Err(PiXtendError::PwmNotConfiguredForDutyCycle(index));
+        }
+
+        // Check that the configured group frequency is within the hardware limit
+        if let PwmConfig::DutyCycle {
+            prescaler,
+            frequency,
+            ..
+        }
+        | PwmConfig::Universal {
+            prescaler,
+            frequency,
+            ..
+        } = self.pwm_configs[index as usize]
+        {
+            check_pwm_frequency(index, prescaler, frequency)?;
+        }
+
+        self.stage_pwm_value(index, channel, value)
+    }
+
+    /// Sets the PWM duty cycle for the given index and channel as a percentage between `0.0` and
+    /// `100.0`, which is mapped onto the configured `frequency` value of the group. Values
+    /// outside of this range are clamped.
+    /// Returns the same errors as `set_pwm_duty_cycle`.
+    ///
+    /// # Example
+    /// We want to set the duty cycle of PWM 0A to `25%`:
+    /// ```no_run
+    /// # use pixtend::{PiXtend, PwmConfig, Channel, PwmPrescaler};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend.set_pwm_config(0, PwmConfig::DutyCycle {
+    ///    prescaler: PwmPrescaler::Prescale62_5kHz,
+    ///    frequency: 31250,
+    ///    channel_a: true,
+    ///    channel_b: true,
+    /// });
+    /// pixtend.set_pwm_duty_cycle_percent(0, Channel::A, 25.0).unwrap();
+    /// ```
+    pub fn set_pwm_duty_cycle_percent(
+        &mut self,
+        index: u8,
+        channel: Channel,
+        percent: f64,
+    ) -> Result<(), PiXtendError> {
+        let frequency = match self.pwm_configs.get(index as usize) {
+            Some(PwmConfig::DutyCycle { frequency, .. })
+            | Some(PwmConfig::Universal { frequency, .. }) => *frequency,
+            Some(_) => return Err(PiXtendError::PwmNotConfiguredForDutyCycle(index)),
+            None => return Err(PiXtendError::InvalidPwmOutputGroupIndex(index)),
+        };
+
+        let value = (percent.clamp(0.0, 100.0) / 100.0 * frequency as f64).round() as u16;
+        self.set_pwm_duty_cycle(index, channel, value)
+    }
+
+    /// Sets the PWM frequency for the given index. The final frequency of the channel is
+    /// calculated with the following formula:
+    /// `frequency = prescaler / 2 / value`
+    /// Returns an error if the configured prescaler is deactivated or if the resulting frequency
+    /// exceeds the hardware maximum of `20kHz`.
+    ///
+    /// # Example
+    /// We want to set the frequency of PWM 0A to `1 Hz`:
+    /// 1 Hz = PwmPrescaler::Prescale62_5kHz / 2 / 31250
+    /// ```no_run
+    /// # use pixtend::{PiXtend, PwmConfig, Channel, PwmPrescaler};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend.set_pwm_config(0, PwmConfig::Frequency {
+    ///     prescaler: PwmPrescaler::Prescale62_5kHz,
+    ///     channel_a: true,
+    ///     channel_b: false,
+    /// }).unwrap();
+    ///
+    /// pixtend.set_pwm_frequency(0, Channel::A, 31250).unwrap();
+    /// ```
+    pub fn set_pwm_frequency(
+        &mut self,
+        index: u8,
+        channel: Channel,
+        value: u16,
+    ) -> Result<(), PiXtendError> {
+        // Check if the given index is valid
+        if index > 2 {
+            return Err(PiXtendError::InvalidPwmOutputGroupIndex(index));
+        }
+
+        // Check if the pwm is configured for frequency
+        let PwmConfig::Frequency { prescaler, .. } = self.pwm_configs[index as usize] else {
+            return Err(PiXtendError::PwmNotConfiguredAsFrequency(index));
+        };
+
+        // Check that the resulting frequency is within the hardware limit
+        check_pwm_frequency(index, prescaler, value)?;
+
+        self.stage_pwm_value(index, channel, value)
+    }
+
+    /// Sets the PWM frequency for the given index and channel in Hz, see `set_pwm_frequency`. The
+    /// value is derived from the base clock of the configured prescaler, see
+    /// `PwmPrescaler::base_hz`, with `value = base_hz / 2 / hz` and rounded to the nearest
+    /// integer, so the output frequency can differ slightly from the requested one, see
+    /// `get_pwm_frequency_hz`.
+    /// Returns an error if the given index is invalid (0 to 2), if the PWM is not configured for
+    /// frequency, if the prescaler is deactivated, if the value doesn't fit into `1` to `65535`
+    /// or if the resulting frequency exceeds `20kHz`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pixtend::{PiXtend, PwmConfig, Channel, PwmPrescaler};
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend.set_pwm_config(0, PwmConfig::Frequency {
+    ///     prescaler: PwmPrescaler::Prescale62_5kHz,
+    ///     channel_a: true,
+    ///     channel_b: false,
+    /// }).unwrap();
+    ///
+    /// pixtend.set_pwm_frequency_hz(0, Channel::A, 1000.0).unwrap();
+    /// ```
+    pub fn set_pwm_frequency_hz(
+        &mut self,
+        index: u8,
+        channel: Channel,
+        hz: f64,
+    ) -> Result<(), PiXtendError> {
+        let prescaler = match self.pwm_configs.get(index as usize) {
+            Some(PwmConfig::Frequency { prescaler, .. }) => *prescaler,
+            Some(_) => return Err(PiXtendError::PwmNotConfiguredAsFrequency(index)),
+            None => return Err(PiXtendError::InvalidPwmOutputGroupIndex(index)),
+        };
+        let base_hz = prescaler
+            .base_hz()
+            .ok_or(PiXtendError::PwmPrescalerDeactivated(index))?;
+
+        // A value of zero or a non-finite result can't be represented by the divider
+        let value = (base_hz as f64 / 2.0 / hz).round();
+        if !(1.0..=u16::MAX as f64).contains(&value) {
+            return Err(PiXtendError::PwmFrequencyUnreachable { index, hz });
+        }
+
+        self.set_pwm_frequency(index, channel, value as u16)
+    }
+
+    /// Sets the values of both channels of the given PWM group at once, which are interpreted
+    /// according to the configured mode like in `set_pwm_servo`, `set_pwm_duty_cycle` and
+    /// `set_pwm_frequency`. Both values are validated before any of them is staged, so the
+    /// channels are never left half updated.
+    /// In Universal mode, channel B is derived from channel A, so only the value of channel A is
+    /// staged and `channel_b` has to be `0`.
+    /// Returns an error if the index is invalid (0 to 2), if the group is deactivated, if
+    /// `channel_b` is not `0` in Universal mode, if a servo value is greater than `16000`, if the
+    /// prescaler is deactivated or if a resulting frequency exceeds `20kHz`.
+    pub fn set_pwm_group_values(
+        &mut self,
+        index: u8,
+        channel_a: u16,
+        channel_b: u16,
+    ) -> Result<(), PiXtendError> {
+        let config = *self
+            .pwm_configs
+            .get(index as usize)
+            .ok_or(PiXtendError::InvalidPwmOutputGroupIndex(index))?;
+
+        match config {
+            PwmConfig::Deactivated => return Err(PiXtendError::PwmGroupDeactivated(index)),
+            PwmConfig::Servo { .. } => {
+                for value in [channel_a, channel_b] {
+                    if value > SERVO_MAX_VALUE {
+                        return Err(PiXtendError::PwmServoValueOutOfRange { index, value });
+                    }
+                }
+            }
+            PwmConfig::DutyCycle {
+                prescaler,
+                frequency,
+                ..
+            }
+            | PwmConfig::Universal {
+                prescaler,
+                frequency,
+                ..
+            } => check_pwm_frequency(index, prescaler, frequency)?,
+            PwmConfig::Frequency { prescaler, .. } => {
+                check_pwm_frequency(index, prescaler, channel_a)?;
+                check_pwm_frequency(index, prescaler, channel_b)?;
+            }
+        }
+
+        // Channel B of a Universal group can't be set, so it is skipped unless a value is given
+        if config.channel_settable(Channel::B) || channel_b != 0 {
+            self.stage_pwm_value(index, Channel::B, channel_b)?;
+        }
+        self.stage_pwm_value(index, Channel::A, channel_a)
+    }
+
+    /// Stages the value of a PWM channel, which every channel setter goes through, so that a
+    /// channel the configured mode doesn't allow to set is never written. The index has to be
+    /// checked by the caller.
+    fn stage_pwm_value(
+        &mut self,
+        index: u8,
+        channel: Channel,
+        value: u16,
+    ) -> Result<(), PiXtendError> {
+        if !self.pwm_configs[index as usize].channel_settable(channel) {
+            return Err(PiXtendError::PwmChannelNotSettable { index, channel });
+        }
+
+        self.output
+            .data
+            .pwm
+            .set_channel_value(index, channel, value)
+    }
+
+    /// Returns the output frequency in Hz that results from the configuration of the given PWM
+    /// group and channel with the formula `frequency = prescaler / 2 / value`. The value is the
+    /// configured `frequency` of the group in DutyCycle and Universal mode, where channel B of a
+    /// Universal group runs at half the frequency of channel A, and the value set via
+    /// `set_pwm_frequency` in Frequency mode. A value of `0` results in `0Hz`.
+    /// Returns an error if the given index is invalid (0 to 2) or if the group is deactivated,
+    /// configured as a servo or has a deactivated prescaler.
+    pub fn get_pwm_frequency_hz(&self, index: u8, channel: Channel) -> Result<f64, PiXtendError> {
+        let config = *self
+            .pwm_configs
+            .get(index as usize)
+            .ok_or(PiXtendError::InvalidPwmOutputGroupIndex(index))?;
+
+        let (prescaler, value, divider) = match (config, channel) {
+            (
+                PwmConfig::DutyCycle {
+                    prescaler,
+                    frequency,
+                    ..
+                },
+                _,
+            )
+            | (
+                PwmConfig::Universal {
+                    prescaler,
+                    frequency,
+                    ..
+                },
+                Channel::A,
+            ) => (prescaler, frequency, 1.0),
+            (
+                PwmConfig::Universal {
+                    prescaler,
+                    frequency,
+                    ..
+                },
+                Channel::B,
+            ) => (prescaler, frequency, 2.0),
+            (PwmConfig::Frequency { prescaler, .. }, _) => (
+                prescaler,
+                self.output.data.pwm.get_channel_value(index, channel)?,
+                1.0,
+            ),
+            (PwmConfig::Deactivated | PwmConfig::Servo { .. }, _) => {
+                return Err(PiXtendError::PwmFrequencyNotConfigured(index))
+            }
+        };
+
+        if value == 0 {
+            return Ok(0.0);
+        }
+
+        pwm_config::frequency_hz(prescaler, value)
+            .map(|hz| hz / divider)
+            .ok_or(PiXtendError::PwmFrequencyNotConfigured(index))
+    }
+
+    /// Retain data can be used to store at most `RETAIN_SIZE` bytes of data in the PiXtend board.
+    /// This data is retained even after a power cycle. The data can be read and written by the
+    /// Raspberry Pi. If less than `RETAIN_SIZE` bytes are passed, the remaining bytes are filled
+    /// with zeros.
+    /// Returns an error if the given data length is greater than `RETAIN_SIZE` or if the retain
+    /// option is not globally enabled via `set_retain_enable`.
+    pub fn set_retain_data(&mut self, data: Vec<u8>) -> Result<(), PiXtendError> {
+        // Check if retain is enabled
+        if !self.output.header.system.retain_enable {
+            return Err(PiXtendError::RetainDataNotGloballyEnabled);
+        }
+
+        self.output.data.retain.set_retain_data(data)
+    }
+
+    /// Copies the given data into the retain storage without taking ownership, see
+    /// `set_retain_data`. If less than `RETAIN_SIZE` bytes are passed, the remaining bytes are
+    /// filled with zeros.
+    /// Returns an error if the given data length is greater than `RETAIN_SIZE` or if the retain
+    /// option is not globally enabled via `set_retain_enable`.
+    pub fn set_retain_slice(&mut self, data: &[u8]) -> Result<(), PiXtendError> {
+        // Check if retain is enabled
+        if !self.output.header.system.retain_enable {
+            return Err(PiXtendError::RetainDataNotGloballyEnabled);
+        }
+
+        self.output.data.retain.set_retain_slice(data)
+    }
+
+    /// Returns the number of bytes available for retain data, which is `RETAIN_SIZE`.
+    pub fn retain_capacity() -> usize {
+        RETAIN_SIZE
+    }
+
+    /// Serializes the given value with the compact `postcard` format and stores it as retain
+    /// data, see `set_retain_data`. Use `get_retain_typed` with the same type to read it back.
+    /// Returns an error if the serialized value is larger than `RETAIN_SIZE` bytes or if the
+    /// retain option is not globally enabled via `set_retain_enable`.
+    #[cfg(feature = "serde")]
+    pub fn set_retain_typed<V: serde::Serialize>(&mut self, value: &V) -> Result<(), PiXtendError> {
+        let data = postcard::to_allocvec(value)?;
+        if data.len() > RETAIN_SIZE {
+            return Err(PiXtendError::RetainDataTooLarge(data.len()));
+        }
+
+        self.set_retain_data(data)
+    }
+
+    /// Writes the given voltage to the analog output with the given channel. The voltage is
+    /// clamped between `0V` and `10V`. If `None` is passed, the analog output is deactivated.
+    pub fn set_analog_output(&mut self, channel: Channel, voltage: Option<f64>) {
+        let dac = match voltage {
+            Some(voltage) => Dac::new(channel, voltage),
+            None => Dac::disabled(channel),
+        };
+        self.dac_configs[channel as usize] = dac;
+    }
+
+    /// Writes the given raw 10 bit value to the analog output with the given channel, bypassing
+    /// the voltage conversion of `set_analog_output`. This is useful when the DAC to output
+    /// transfer curve has been characterized. The value is clamped between `0` and `1023`.
+    pub fn set_analog_output_raw(&mut self, channel: Channel, value: u16) {
+        self.dac_configs[channel as usize] = Dac::from_raw(channel, value);
+    }
+
+    /// Returns the staged voltage of the analog output with the given channel, reconstructed
+    /// from the 10 bit DAC value. Returns `None` if the analog output is deactivated.
+    pub fn get_analog_output(&self, channel: Channel) -> Option<f64> {
+        self.dac_configs[channel as usize].voltage()
+    }
+
+    /// Checks the staged GPIO and PWM configurations as a whole, which is also done before every
+    /// transfer of `read_write` and `write`. The individual setters only check a change against
+    /// the current state, so changing a global setting afterwards can leave an inconsistent
+    /// configuration behind.
+    /// Returns an error in the following cases:
+    /// - A GPIO is configured as a sensor while a PWM output group is configured
+    /// - A GPIO input has its pullup resistor enabled, but the pullups are not enabled globally
+    ///   via `set_gpio_pullup_enable`
+    /// - The retain copy is set, but the retain function is not enabled via `set_retain_enable`,
+    ///   unless this check is disabled via `set_retain_copy_check`
+    pub fn validate_config(&self) -> Result<(), PiXtendError> {
+        if self.gpio_configs.contains(&GpioConfig::Sensor)
+            && self
+                .pwm_configs
+                .iter()
+                .any(|config| *config != PwmConfig::Deactivated)
+        {
+            return Err(PiXtendError::PwmAndDhtExclusive);
+        }
+
+        if self.gpio_configs.contains(&GpioConfig::Input(true))
+            && !self.output.header.system.gpio_pullup_enable
+        {
+            return Err(PiXtendError::GpioPullupNotGloballyEnabled);
+        }
+
+        let system = &self.output.header.system;
+        if self.retain_copy_check && system.retain_copy && !system.retain_enable {
+            return Err(PiXtendError::RetainCopyWithoutEnable);
+        }
+
+        Ok(())
+    }
+
+    /// Configures whether the analog output values are written to the DAC in every cycle of
+    /// `read_write` and `write`. Disabling it saves the SPI traffic of the DAC on boards without
+    /// analog output wiring. When it is enabled again, the last set analog output values are
+    /// written with the next cycle. The default is `true`.
+    pub fn set_dac_enabled(&mut self, value: bool) {
+        self.dac_enabled = value;
+    }
+
+    /// Writes the staged analog output values to the DAC right away, without transferring the
+    /// PiXtend frame and without waiting for the cycle delay. The DAC is selected via its own
+    /// chip select, so the analog outputs can be updated faster than the PiXtend cycle.
+    /// The values are written regardless of `set_dac_enabled`. Since `read_write` and `write`
+    /// write the DAC values again in every cycle, disable this with `set_dac_enabled(false)` if
+    /// the analog outputs are only updated via this function, to avoid writing them twice.
+    pub fn write_dac(&mut self) -> Result<(), PiXtendError> {
+        let mut spi_dac = lock(&self.spi_dac);
+        for frame in self.dac_frames()? {
+            spi_dac.write(&frame)?;
+        }
+
+        Ok(())
+    }
+
+    /// Serializes the staged values of both DACs
+    fn dac_frames(&self) -> Result<Vec<Vec<u8>>, PiXtendError> {
+        Ok(vec![
+            self.dac_configs[0].to_bytes()?,
+            self.dac_configs[1].to_bytes()?,
+        ])
+    }
+
+    /// Returns all currently staged configuration options, i.e. to store them in a file.
+    pub fn get_config(&self) -> PiXtendConfig {
+        let system = &self.output.header.system;
+
+        PiXtendConfig {
+            watchdog: self.output.header.watchdog,
+            retain_copy: system.retain_copy,
+            retain_enable: system.retain_enable,
+            led_disable: system.led_disable,
+            gpio_pullup_enable: system.gpio_pullup_enable,
+            digital_debounce: self.output.data.digital_debounce.values(),
+            gpio_debounce: self.output.data.gpio_debounce.values(),
+            gpio_configs: self.gpio_configs,
+            pwm_configs: self.pwm_configs,
+        }
+    }
+
+    /// Applies all configuration options of the given configuration. The global settings are
+    /// applied first, followed by the GPIO and PWM configurations in an order that resolves the
+    /// PWM and DHT exclusivity.
+    /// Returns the same errors as the individual setters, i.e. if a sensor GPIO and a PWM output
+    /// are configured at the same time.
+    pub fn apply_config(&mut self, config: &PiXtendConfig) -> Result<(), PiXtendError> {
+        self.set_watchdog(config.watchdog);
+        self.set_retain_copy(config.retain_copy);
+        self.set_retain_enable(config.retain_enable);
+        self.set_led_disable(config.led_disable);
+        self.set_gpio_pullup_enable(config.gpio_pullup_enable);
+
+        for (group, value) in (0..).zip(config.digital_debounce) {
+            self.set_digital_debounce(group, value)?;
+        }
+        for (group, value) in (0..).zip(config.gpio_debounce) {
+            self.set_gpio_debounce(group, value)?;
+        }
+
+        // Removing sensors before configuring the PWM outputs and adding sensors afterwards
+        // avoids false exclusivity errors caused by the previous configuration
+        for (index, gpio_config) in (0..).zip(config.gpio_configs) {
+            if gpio_config != GpioConfig::Sensor {
+                self.set_gpio_config(index, gpio_config)?;
+            }
+        }
+        for (index, pwm_config) in (0..).zip(config.pwm_configs) {
+            self.set_pwm_config(index, pwm_config)?;
+        }
+        for (index, gpio_config) in (0..).zip(config.gpio_configs) {
+            if gpio_config == GpioConfig::Sensor {
+                self.set_gpio_config(index, gpio_config)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the firmware version of the PiXtend board.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_firmware_version(&self) -> Result<u8, PiXtendError> {
+        self.input
+            .as_ref()
+            .map(|input| input.header.firmware)
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Checks that the firmware version of the PiXtend board is at least `min`, i.e. as a startup
+    /// gate for features that depend on a firmware revision.
+    /// Returns an error if the firmware version is older than `min`.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn require_firmware(&self, min: u8) -> Result<(), PiXtendError> {
+        let found = self.get_firmware_version()?;
+        if found < min {
+            return Err(PiXtendError::FirmwareTooOld {
+                found,
+                required: min,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Reads the hardware version of the PiXtend board.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_hardware_version(&self) -> Result<u8, PiXtendError> {
+        self.input
+            .as_ref()
+            .map(|input| input.header.hardware)
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Reads the hardware version of the PiXtend board decoded into its revision, see
+    /// `HardwareRevision`.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_hardware_revision(&self) -> Result<HardwareRevision, PiXtendError> {
+        self.get_hardware_version().map(HardwareRevision::from_byte)
+    }
+
+    /// Reads the firmware version, hardware version and model of the PiXtend board at once, i.e.
+    /// for logging at startup or checking for a minimum firmware version.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_versions(&self) -> Result<Versions, PiXtendError> {
+        self.input
+            .as_ref()
+            .map(|input| Versions {
+                firmware: input.header.firmware,
+                hardware: input.header.hardware,
+                model: input.header.model as char,
+            })
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Returns whether the microcontroller of the PiXtend board is running. If it is not running,
+    /// i.e. because it entered the safe state, the next `read_write` returns
+    /// `PiXtendError::NotReadyForCommunication`.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn is_running(&self) -> Result<bool, PiXtendError> {
+        self.input
+            .as_ref()
+            .map(|input| input.header.state.run)
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Returns what the status LED "L1" of the PiXtend board currently indicates, i.e. to mirror
+    /// it in a user interface. The state is decoded from the LED configuration, the run flag of
+    /// the last input frame and the last reported error code, see `last_error_code`.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn led_meaning(&self) -> Result<LedState, PiXtendError> {
+        let input = self
+            .input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?;
+
+        if self.output.header.system.led_disable {
+            return Ok(LedState::Disabled);
+        }
+
+        if !input.header.state.run {
+            return Ok(LedState::SafeState);
+        }
+
+        Ok(match self.last_error_code {
+            Some(ErrorCode::NoError) | None => LedState::Running,
+            Some(error_code) => LedState::Error(error_code),
+        })
+    }
+
+    /// Returns the raw error code that the PiXtend board reported in the last received frame
+    /// with a valid CRC, without it being mapped to an error as in `read_write`. In contrast to
+    /// the other input data, the error code is also stored if `read_write` returned an error
+    /// because of it. The remaining bits of the state byte are reserved, so the cause of the last
+    /// reset, i.e. the watchdog, is not reported.
+    /// Returns an error if no valid frame has been received yet via `read_write`.
+    pub fn last_error_code(&self) -> Result<ErrorCode, PiXtendError> {
+        self.last_error_code
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Returns the warnings that the PiXtend board reports. The warnings are:
+    /// - `i2c_error`: An I2C error occurred between the PiXtend board and the Raspberry Pi
+    /// - `voltage_error`: The voltage supply of the PiXtend board dropped below 19V. As a result,
+    ///   the retain memory functionality is not available
+    /// - `retain_crc_error`: The CRC check of the retain memory failed
+    ///
+    /// The other bits of the warning byte are reserved and not reported.
+    pub fn get_warnings(&self) -> Result<Warnings, PiXtendError> {
+        self.input
+            .as_ref()
+            .map(|input| input.header.warnings)
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Returns whether the PiXtend board reported an I2C error in any frame since the creation of
+    /// the instance or the last call of `clear_warning_latches`. In contrast to `get_warnings`,
+    /// this catches transient warnings between two polls.
+    pub fn i2c_error_latched(&self) -> bool {
+        self.warning_latches.i2c_error
+    }
+
+    /// Returns whether the PiXtend board reported a voltage error in any frame since the creation
+    /// of the instance or the last call of `clear_warning_latches`, see `i2c_error_latched`.
+    pub fn voltage_error_latched(&self) -> bool {
+        self.warning_latches.voltage_error
+    }
+
+    /// Returns whether the PiXtend board reported a retain CRC error in any frame since the
+    /// creation of the instance or the last call of `clear_warning_latches`, see
+    /// `i2c_error_latched`.
+    pub fn retain_crc_error_latched(&self) -> bool {
+        self.warning_latches.retain_crc_error
+    }
+
+    /// Clears the latched warnings of `i2c_error_latched`, `voltage_error_latched` and
+    /// `retain_crc_error_latched`.
+    pub fn clear_warning_latches(&mut self) {
+        self.warning_latches = Warnings::default();
+    }
+
+    /// Returns the values of all inputs at once, which are guaranteed to be taken from the same
+    /// input frame. The analog voltage inputs use the reference voltages set via
+    /// `set_reference_voltage`. In contrast to `get_gpio_input`, the GPIO inputs are returned
+    /// regardless of the GPIO configuration.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn snapshot(&self) -> Result<InputSnapshot, PiXtendError> {
+        let input = self
+            .input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?;
+        let analog_in = &input.data.analog_in;
+        let gpio_in = &input.data.gpio_in;
+        let raw = analog_in.raw_values();
+
+        Ok(InputSnapshot {
+            digital_inputs: input.data.digital_in.as_mask(),
+            analog_voltages: [0, 1, 2, 3].map(|index| {
+                input::raw_to_voltage(raw[index] as f64, self.reference_voltages[index])
+            }),
+            analog_currents: [4, 5]
+                .map(|index| self.calibrate_current(index, raw[index as usize] as f64)),
+            gpio_inputs: [gpio_in.in0, gpio_in.in1, gpio_in.in2, gpio_in.in3],
+            warnings: input.header.warnings,
+        })
+    }
+
+    /// Returns a one-line human readable summary of the board state, consisting of the versions,
+    /// the run state, the active warnings and the last error code, i.e. for debugging:
+    /// `PiXtend L firmware 5 hardware 21, running, warnings: none, error code: NoError`
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn status_summary(&self) -> Result<String, PiXtendError> {
+        let versions = self.get_versions()?;
+        let warnings = self.get_warnings()?;
+
+        let active_warnings = [
+            (warnings.i2c_error, "I2C error"),
+            (warnings.voltage_error, "voltage error"),
+            (warnings.retain_crc_error, "retain CRC error"),
+        ]
+        .into_iter()
+        .filter_map(|(active, name)| active.then_some(name))
+        .collect::<Vec<_>>();
+
+        Ok(format!(
+            "PiXtend {} firmware {} hardware {}, {}, warnings: {}, error code: {:?}",
+            versions.model,
+            versions.firmware,
+            versions.hardware,
+            match self.is_running()? {
+                true => "running",
+                false => "not running",
+            },
+            match active_warnings.is_empty() {
+                true => "none".to_string(),
+                false => active_warnings.join(", "),
+            },
+            self.last_error_code()?,
+        ))
+    }
+
+    /// Reads the digital input at the given index.
+    /// Valid indexes are `0` to `15`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_digital_input(&self, index: u8) -> Result<bool, PiXtendError> {
+        self.input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?
+            .data
+            .digital_in
+            .get_digital_input(index)
+    }
+
+    /// Reads all digital inputs at once, where bit N of the returned mask is the value of digital
+    /// input N.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_digital_inputs(&self) -> Result<u16, PiXtendError> {
+        self.input
+            .as_ref()
+            .map(|input| input.data.digital_in.as_mask())
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Returns a mask of the digital inputs that changed from low to high between the previous
+    /// and the current cycle, where bit N corresponds to digital input N. The first cycle after
+    /// creation or `reset` reports no edges, since there is no previous cycle to compare with.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn rising_edges(&self) -> Result<u16, PiXtendError> {
+        let (previous, current) = self.digital_input_transition()?;
+        Ok(current & !previous)
+    }
+
+    /// Returns a mask of the digital inputs that changed from high to low between the previous
+    /// and the current cycle, where bit N corresponds to digital input N. The first cycle after
+    /// creation or `reset` reports no edges, since there is no previous cycle to compare with.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn falling_edges(&self) -> Result<u16, PiXtendError> {
+        let (previous, current) = self.digital_input_transition()?;
+        Ok(previous & !current)
+    }
+
+    /// Returns an event for each digital input that changed between the previous and the current
+    /// cycle, ordered by the input index. All events carry the completion time of the current
+    /// cycle. Like the edge masks, the first cycle after creation or `reset` reports no events.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn input_events(&self) -> Result<Vec<InputEvent>, PiXtendError> {
+        let (previous, current) = self.digital_input_transition()?;
+        let at = self
+            .last_completion
+            .ok_or(PiXtendError::NoInputDataAvailable)?;
+
+        Ok((0..16)
+            .filter(|index| (previous ^ current) & (1 << index) != 0)
+            .map(|index| InputEvent {
+                index,
+                rising: current & (1 << index) != 0,
+                at,
+            })
+            .collect())
+    }
+
+    /// Returns the digital input masks of the previous and the current cycle
+    fn digital_input_transition(&self) -> Result<(u16, u16), PiXtendError> {
+        let current = self.get_digital_inputs()?;
+        let previous = self.previous_digital_inputs.unwrap_or(current);
+        Ok((previous, current))
+    }
+
+    /// Sets the reference voltage of the analog voltage input at the given index, which is used
+    /// by `get_analog_voltage_input`. The reference voltage can be set to either
+    /// `ReferenceVoltage::V5` for a 0V to 5V range or `ReferenceVoltage::V10` for a 0V to 10V
+    /// range and must match the jumper of the input on the PiXtend board. The default is 0V to
+    /// 10V for all inputs.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    pub fn set_reference_voltage(
+        &mut self,
+        index: u8,
+        reference_voltage: ReferenceVoltage,
+    ) -> Result<(), PiXtendError> {
+        *self
+            .reference_voltages
+            .get_mut(index as usize)
+            .ok_or(PiXtendError::InvalidAnalogVoltageInputIndex(index))? = reference_voltage;
+
+        Ok(())
+    }
+
+    /// Reads the analog voltage input at the given index in volts, using the reference voltage
+    /// set via `set_reference_voltage`.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_voltage_input(&self, index: u8) -> Result<f64, PiXtendError> {
+        let reference_voltage = *self
+            .reference_voltages
+            .get(index as usize)
+            .ok_or(PiXtendError::InvalidAnalogVoltageInputIndex(index))?;
+
+        self.get_analog_voltage_input_with_reference(index, reference_voltage)
+    }
+
+    /// Reads the analog voltage input at the given index in volts with an explicit reference
+    /// voltage, ignoring the one set via `set_reference_voltage`. The reference voltage can be
+    /// set to either `ReferenceVoltage::V5` for a 0V to 5V range or `ReferenceVoltage::V10` for
+    /// a 0V to 10V range. This range is set via jumpers on the PiXtend board.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_voltage_input_with_reference(
+        &self,
+        index: u8,
+        reference_voltage: ReferenceVoltage,
+    ) -> Result<f64, PiXtendError> {
+        self.input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?
+            .data
+            .analog_in
+            .get_analog_voltage_input(index, reference_voltage)
+    }
+
+    /// Reads the analog current input at the given index in milliamperes, or in the unit of the
+    /// calibration set via `set_current_calibration`.
+    /// Valid indexes are `4` and `5`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_current_input(&self, index: u8) -> Result<f64, PiXtendError> {
+        if !(4..=5).contains(&index) {
+            return Err(PiXtendError::InvalidAnalogCurrentInputIndex(index));
+        }
+
+        let raw = self.get_analog_raw(index)?;
+        Ok(self.calibrate_current(index, raw as f64))
+    }
+
+    /// Reads the analog current input at the given index as a fraction of the 4mA to 20mA span
+    /// of industrial sensors, where 4mA is `0.0` and 20mA is `1.0`. Currents outside of the span
+    /// are clamped. The conversion is based on the calibrated value of
+    /// `get_analog_current_input`, so a calibration set via `set_current_calibration` has to
+    /// keep the result in milliamperes.
+    /// Valid indexes are `4` and `5`, returns an error if the index is invalid.
+    /// Returns an error if the current is below 3.5mA, which indicates a broken wire.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_current_percent(&self, index: u8) -> Result<f64, PiXtendError> {
+        let milliamperes = self.get_analog_current_input(index)?;
+        if milliamperes < CURRENT_LOOP_BROKEN_MA {
+            return Err(PiXtendError::CurrentLoopBroken {
+                index,
+                milliamperes,
+            });
+        }
+
+        Ok(((milliamperes - 4.0) / 16.0).clamp(0.0, 1.0))
+    }
+
+    /// Sets the calibration of the analog current input at the given index, which converts the
+    /// raw ADC count as `raw * scale + offset` in `get_analog_current_input` and
+    /// `get_analog_current_input_averaged`. The default is a scale of `0.020158400229358` and an
+    /// offset of `0`, which results in milliamperes. This allows mapping the raw count directly to
+    /// engineering units, i.e. subtracting the live zero of a 4mA to 20mA sensor.
+    /// Valid indexes are `4` and `5`, returns an error if the index is invalid.
+    ///
+    /// # Example
+    /// We want to read a 4mA to 20mA pressure sensor on current input 4 in bar (0 to 10bar):
+    /// ```no_run
+    /// # use pixtend::PiXtend;
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// let ma_per_count = 0.020158400229358;
+    /// let bar_per_ma = 10.0 / 16.0;
+    /// pixtend
+    ///     .set_current_calibration(4, ma_per_count * bar_per_ma, -4.0 * bar_per_ma)
+    ///     .unwrap();
+    /// ```
+    pub fn set_current_calibration(
+        &mut self,
+        index: u8,
+        scale: f64,
+        offset: f64,
+    ) -> Result<(), PiXtendError> {
+        if !(4..=5).contains(&index) {
+            return Err(PiXtendError::InvalidAnalogCurrentInputIndex(index));
+        }
+
+        self.current_calibrations[index as usize - 4] = (scale, offset);
+        Ok(())
+    }
+
+    /// Applies the calibration of the current input with the given index to the raw value
+    fn calibrate_current(&self, index: u8, raw: f64) -> f64 {
+        let (scale, offset) = self.current_calibrations[index as usize - 4];
+        raw * scale + offset
+    }
+
+    /// Reads the raw 10 bit ADC count of the analog input at the given index. Indexes `0` to `3`
+    /// are the voltage inputs, `4` and `5` the current inputs.
+    /// Valid indexes are `0` to `5`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_raw(&self, index: u8) -> Result<u16, PiXtendError> {
+        self.input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?
+            .data
+            .analog_in
+            .get_analog_raw(index)
+    }
+
+    /// Returns whether the analog input at the given index is clipped at either end of the 10 bit
+    /// ADC range, i.e. the raw count is `0` or `1023`. A reading pinned at the maximum usually
+    /// means the input exceeds the range of the selected reference voltage jumper.
+    /// Valid indexes are `0` to `5`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn analog_input_saturated(&self, index: u8) -> Result<bool, PiXtendError> {
+        self.input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?
+            .data
+            .analog_in
+            .is_saturated(index)
+    }
+
+    /// Sets the number of cycles that are averaged by `get_analog_voltage_input_averaged` and
+    /// `get_analog_current_input_averaged`. The default is 8 cycles, a size of `0` is treated
+    /// as `1`. When the size is reduced, the oldest samples are dropped.
+    pub fn set_analog_average_size(&mut self, size: usize) {
+        self.analog_average.set_size(size);
+    }
+
+    /// Reads the analog voltage input at the given index in volts, averaged over the last
+    /// `read_write` cycles and using the reference voltage set via `set_reference_voltage`. The
+    /// average only becomes meaningful after enough cycles have been collected, see
+    /// `set_analog_average_size`.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_voltage_input_averaged(&self, index: u8) -> Result<f64, PiXtendError> {
+        let reference_voltage = *self
+            .reference_voltages
+            .get(index as usize)
+            .ok_or(PiXtendError::InvalidAnalogVoltageInputIndex(index))?;
+
+        self.analog_average
+            .mean(index as usize)
+            .map(|raw| input::raw_to_voltage(raw, reference_voltage))
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Reads the analog current input at the given index in milliamperes, or in the unit of the
+    /// calibration set via `set_current_calibration`, averaged over the last `read_write`
+    /// cycles. The average only becomes meaningful after enough cycles have been collected, see
+    /// `set_analog_average_size`.
+    /// Valid indexes are `4` and `5`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_current_input_averaged(&self, index: u8) -> Result<f64, PiXtendError> {
+        if !(4..=5).contains(&index) {
+            return Err(PiXtendError::InvalidAnalogCurrentInputIndex(index));
+        }
+
+        self.analog_average
+            .mean(index as usize)
+            .map(|raw| self.calibrate_current(index, raw))
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Sets the weight of a new sample in the exponential moving average low-pass filter of the
+    /// analog input at the given index, which is applied in every `read_write` cycle. The
+    /// filtered value is calculated as `filtered + alpha * (sample - filtered)`, so smaller values
+    /// smooth the input more strongly. The default of `1.0` passes the samples through
+    /// unfiltered. The filter starts at the first sample, so there is no ramp from zero. The
+    /// filtered values are read via `get_analog_voltage_input_filtered` and
+    /// `get_analog_current_input_filtered`.
+    /// Valid indexes are `0` to `5`, returns an error if the index is invalid or if the weight
+    /// is not between `0.0` and `1.0`.
+    pub fn set_analog_filter(&mut self, index: u8, alpha: f64) -> Result<(), PiXtendError> {
+        if index > 5 {
+            return Err(PiXtendError::InvalidAnalogVoltageInputIndex(index));
+        }
+
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(PiXtendError::InvalidAnalogFilterAlpha(alpha));
+        }
+
+        self.analog_filter.set_alpha(index as usize, alpha);
+        Ok(())
+    }
+
+    /// Reads the analog voltage input at the given index in volts, smoothed by the low-pass
+    /// filter configured via `set_analog_filter` and using the reference voltage set via
+    /// `set_reference_voltage`.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_voltage_input_filtered(&self, index: u8) -> Result<f64, PiXtendError> {
+        let reference_voltage = *self
+            .reference_voltages
+            .get(index as usize)
+            .ok_or(PiXtendError::InvalidAnalogVoltageInputIndex(index))?;
+
+        self.analog_filter
+            .value(index as usize)
+            .map(|raw| input::raw_to_voltage(raw, reference_voltage))
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Reads the analog current input at the given index in milliamperes, or in the unit of the
+    /// calibration set via `set_current_calibration`, smoothed by the low-pass filter configured
+    /// via `set_analog_filter`.
+    /// Valid indexes are `4` and `5`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_analog_current_input_filtered(&self, index: u8) -> Result<f64, PiXtendError> {
+        if !(4..=5).contains(&index) {
+            return Err(PiXtendError::InvalidAnalogCurrentInputIndex(index));
+        }
+
+        self.analog_filter
+            .value(index as usize)
+            .map(|raw| self.calibrate_current(index, raw))
+            .ok_or(PiXtendError::NoInputDataAvailable)
+    }
+
+    /// Reads the GPIO input at the given index.
+    /// If the GPIO is not configured as an input, an error is returned.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_gpio_input(&self, index: u8) -> Result<bool, PiXtendError> {
+        // Check if the gpio is configured as an input
+        if !matches!(
+            self.gpio_configs.get(index as usize),
+            Some(GpioConfig::Input(_)),
+        ) {
+            return Err(PiXtendError::GpioNotConfiguredAsInput(index));
+        }
+
+        self.input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?
+            .data
+            .gpio_in
+            .get_gpio_input(index)
+    }
+
+    /// Returns whether the pullup resistor of the GPIO at the given index is effectively active,
+    /// which requires both the `GpioConfig::Input(true)` configuration of the GPIO and the global
+    /// pullup enable bit set via `set_gpio_pullup_enable`. This reflects the staged configuration.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    pub fn gpio_pullup_active(&self, index: u8) -> Result<bool, PiXtendError> {
+        let config = self
+            .gpio_configs
+            .get(index as usize)
+            .ok_or(PiXtendError::InvalidGpioInputIndex(index))?;
+
+        Ok(*config == GpioConfig::Input(true) && self.output.header.system.gpio_pullup_enable)
+    }
+
+    /// Reads the temperature from a DHT11/DHT22/AM2302 onewire sensor connected to the given GPIO
+    /// index. The sensor type must be specified to return the calculated temperature in Celsius.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    /// Returns an error if the reading is outside of the measurement range of the sensor.
+    /// Returns an error if the sensor is not responding, see `set_sensor_timeout_cycles`.
+    /// Returns an error if the GPIO is not configured as a sensor.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_gpio_temperature(&self, index: u8, sensor: SensorKind) -> Result<f64, PiXtendError> {
+        // Check if the gpio is configured as a sensor
+        if !matches!(
+            self.gpio_configs.get(index as usize),
+            Some(GpioConfig::Sensor),
+        ) {
+            return Err(PiXtendError::GpioNotConfiguredAsSensor(index));
+        }
+
+        // Check if the sensor delivered data in the last cycles
+        self.check_sensor_responding(index)?;
+
+        let input = self
+            .input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?;
+
+        // Prefer the median of the buffered readings if the smoothing is enabled
+        let median = self.sensor_smoothing.median(index as usize, |reading| {
+            reading.plausible_temperature(sensor)
+        });
+        match median {
+            Some(celsius) => Ok(celsius),
+            None => input.data.sensor_in.get_temperature_input(index, sensor),
+        }
+    }
+
+    /// Sets the number of consecutive cycles in which both sensor registers must be zero, until
+    /// the sensor getters return `PiXtendError::SensorNotResponding`. The PiXtend reports zeros if
+    /// no sensor responds, so this distinguishes a disconnected sensor from a genuine reading of
+    /// 0°C and 0%. The default is 3 cycles, a value of `0` disables the detection.
+    pub fn set_sensor_timeout_cycles(&mut self, cycles: u32) {
+        self.sensor_timeout_cycles = cycles;
+    }
+
+    /// Configures the detection of a stalled SPI link or firmware, which keeps returning the same
+    /// input frame with a valid CRC. With `Some(frames)`, `read_write` returns
+    /// `PiXtendError::LinkStalled` once that many consecutive input frames are byte-for-byte
+    /// identical. The input frame of the PiXtend V2 -L- contains no cycle counter, so inputs that
+    /// legitimately stay constant look the same, which should be considered when choosing the
+    /// number of frames. The default is `None`, which disables the detection.
+    /// Returns an error if the number of frames is less than `2`, since a single frame can't be
+    /// identical to a previous one.
+    pub fn set_stall_detection(&mut self, frames: Option<usize>) -> Result<(), PiXtendError> {
+        if let Some(frames @ 0..=1) = frames {
+            return Err(PiXtendError::InvalidStallThreshold(frames));
+        }
+
+        self.stall_detection = frames;
+        Ok(())
+    }
+
+    /// Starts recording the TX and RX frames of every transfer to the given file for offline
+    /// debugging, see `replay` to read them back. Transfers of `write` and `flush` don't read a
+    /// response, so they are recorded with an empty RX frame. The writes to the DAC are not
+    /// recorded. The frames are appended to an existing file. A recording that was already
+    /// running is closed first. If writing a frame fails, the recording is stopped, so that a
+    /// full disk doesn't break the control loop.
+    /// Returns an error if the file can't be opened.
+    pub fn record_to(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), PiXtendError> {
+        self.recorder = Some(Recorder::open(path)?);
+        Ok(())
+    }
+
+    /// Pauses or resumes the recording started via `record_to`, without closing the file.
+    pub fn set_recording(&mut self, enabled: bool) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.enabled = enabled;
+        }
+    }
+
+    /// Stops the recording started via `record_to` and flushes the remaining frames to the file.
+    /// The recording is also flushed when the instance is dropped.
+    /// Returns an error if the remaining frames can't be written.
+    pub fn stop_recording(&mut self) -> Result<(), PiXtendError> {
+        match self.recorder.take() {
+            Some(mut recorder) => recorder.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Appends the frames of one transfer to the recording, if any
+    fn record(&mut self, tx: &[u8], rx: &[u8]) {
+        let Some(recorder) = self.recorder.as_mut() else {
+            return;
+        };
+
+        if let Err(_error) = recorder.record(tx, rx) {
+            log_error!("Stopping the recording after a write error: {}", _error);
+            self.recorder = None;
+        }
+    }
+
+    /// Enables the smoothing of the sensor connected to the GPIO with the given index, so that
+    /// `get_gpio_temperature` and `get_gpio_humidity` return the median of the plausible values
+    /// of the last `samples` readings instead of only the current one. Readings of a not
+    /// responding sensor are skipped. Until the first plausible reading is buffered, the current
+    /// reading is returned as without smoothing. A value of `0` or `1` disables the smoothing,
+    /// which is the default.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    pub fn set_sensor_smoothing(&mut self, index: u8, samples: usize) -> Result<(), PiXtendError> {
+        if index > 3 {
+            return Err(PiXtendError::InvalidGpioInputIndex(index));
+        }
+
+        self.sensor_smoothing.set_size(index as usize, samples);
+        Ok(())
+    }
+
+    /// Returns an error if the sensor with the given index reported no data for too many cycles
+    fn check_sensor_responding(&self, index: u8) -> Result<(), PiXtendError> {
+        let empty_cycles = self.sensor_empty_cycles[index as usize];
+        match self.sensor_timeout_cycles > 0 && empty_cycles >= self.sensor_timeout_cycles {
+            true => Err(PiXtendError::SensorNotResponding { index }),
+            false => Ok(()),
+        }
+    }
+
+    /// Reads the temperature from a DHT11/DHT22/AM2302 onewire sensor connected to the given GPIO
+    /// index in the given unit. See `get_gpio_temperature` for details.
+    pub fn get_gpio_temperature_in(
+        &self,
+        index: u8,
+        sensor: SensorKind,
+        unit: TemperatureUnit,
+    ) -> Result<f64, PiXtendError> {
+        self.get_gpio_temperature(index, sensor)
+            .map(|celsius| unit.from_celsius(celsius))
+    }
+
+    /// Reads the humidity from a DHT11/DHT22/AM2302 onewire sensor connected to the given GPIO
+    /// index. The sensor type must be specified to return the calculated humidity as a percentage
+    /// from 0.0 to 1.0.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid.
+    /// Returns an error if the reading is outside of the measurement range of the sensor.
+    /// Returns an error if the sensor is not responding, see `set_sensor_timeout_cycles`.
+    /// Returns an error if the GPIO is not configured as a sensor.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_gpio_humidity(&self, index: u8, sensor: SensorKind) -> Result<f64, PiXtendError> {
+        // Check if the gpio is configured as a sensor
+        if !matches!(
+            self.gpio_configs.get(index as usize),
+            Some(GpioConfig::Sensor),
+        ) {
+            return Err(PiXtendError::GpioNotConfiguredAsSensor(index));
+        }
+
+        // Check if the sensor delivered data in the last cycles
+        self.check_sensor_responding(index)?;
+
+        let input = self
+            .input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?;
+
+        // Prefer the median of the buffered readings if the smoothing is enabled
+        let median = self
+            .sensor_smoothing
+            .median(index as usize, |reading| reading.plausible_humidity(sensor));
+        match median {
+            Some(humidity) => Ok(humidity),
+            None => input.data.sensor_in.get_humidity_input(index, sensor),
+        }
+    }
+
+    /// Reads the retain data that the PiXtend board returns. Depending on the value of
+    /// `set_retain_copy`, this can be the last saved data or the last data sent by the Raspberry Pi.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_retain_data(&self) -> Result<Vec<u8>, PiXtendError> {
+        Ok(self
+            .input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?
+            .data
+            .retain
+            .clone())
+    }
+
+    /// Borrows the retain data that the PiXtend board returns without allocating, see
+    /// `get_retain_data`.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn retain_slice(&self) -> Result<&[u8], PiXtendError> {
+        Ok(&self
+            .input
+            .as_ref()
+            .ok_or(PiXtendError::NoInputDataAvailable)?
+            .data
+            .retain)
+    }
+
+    /// Copies the retain data that the PiXtend board returns into a fixed-size array without
+    /// allocating, see `get_retain_data`.
+    /// Returns an error if the input data has not been read yet via `read_write`.
+    pub fn get_retain_array(&self) -> Result<[u8; RETAIN_SIZE], PiXtendError> {
+        let mut retain = [0; RETAIN_SIZE];
+        retain.copy_from_slice(self.retain_slice()?);
+        Ok(retain)
+    }
+
+    /// Deserializes the retain data that the PiXtend board returns into a value stored via
+    /// `set_retain_typed`, see `get_retain_data`.
+    /// Returns an error if the input data has not been read yet via `read_write` or if the retain
+    /// data doesn't contain a valid value of the given type.
+    #[cfg(feature = "serde")]
+    pub fn get_retain_typed<V: serde::de::DeserializeOwned>(&self) -> Result<V, PiXtendError> {
+        Ok(postcard::from_bytes(&self.get_retain_data()?)?)
+    }
+
+    /// Returns the raw bytes of the output frame. The CRC values are the ones calculated during
+    /// the last `read_write` or `write`, so after a cycle this is exactly the frame that was sent.
+    pub fn last_output_bytes(&self) -> Result<Vec<u8>, PiXtendError> {
+        Ok(self.output.to_bytes()?)
+    }
+
+    /// Returns the raw bytes of the input frame that the currently stored input data was parsed
+    /// from, or `None` if no input data has been read yet via `read_write`.
+    pub fn last_input_bytes(&self) -> Option<Vec<u8>> {
+        self.input_bytes.clone()
+    }
+
+    /// Calculates the CRCs of the staged output frame without transmitting it and returns them as
+    /// `(header_crc, data_crc)`, i.e. to compare the frame construction against test vectors of
+    /// the PiXtend reference implementation.
+    pub fn output_crcs(&mut self) -> Result<(u16, u16), PiXtendError> {
+        self.output.update()?;
+        Ok(self.output.crcs())
+    }
+
+    /// Returns the decoded values of all fields of the staged output frame and of the DAC words,
+    /// i.e. to compare the configuration with the PiXtend reference tool. In contrast to
+    /// `last_output_bytes`, the fields don't have to be unpacked from the bit layout.
+    pub fn output_debug(&self) -> OutputDebug {
+        let header = &self.output.header;
+        let data = &self.output.data;
+        let gpio_ctrl = &data.gpio_ctrl;
+        let gpio_out = &data.gpio_out;
+
+        OutputDebug {
+            model: header.model,
+            watchdog: header.watchdog,
+            safe: header.system.safe,
+            retain_copy: header.system.retain_copy,
+            retain_enable: header.system.retain_enable,
+            led_disable: header.system.led_disable,
+            gpio_pullup_enable: header.system.gpio_pullup_enable,
+            digital_debounce: data.digital_debounce.values(),
+            digital_outputs: data.digital_out.as_mask(),
+            relay_outputs: data.relay_out.as_mask(),
+            gpio_io: [gpio_ctrl.io0, gpio_ctrl.io1, gpio_ctrl.io2, gpio_ctrl.io3],
+            gpio_sensors: [
+                gpio_ctrl.sens0,
+                gpio_ctrl.sens1,
+                gpio_ctrl.sens2,
+                gpio_ctrl.sens3,
+            ],
+            gpio_outputs: [
+                gpio_out.gpio0,
+                gpio_out.gpio1,
+                gpio_out.gpio2,
+                gpio_out.gpio3,
+            ],
+            gpio_debounce: data.gpio_debounce.values(),
+            pwm: [&data.pwm.group0, &data.pwm.group1, &data.pwm.group2].map(|group| {
+                PwmGroupDebug {
+                    mode: group.ctrl0.mode,
+                    prescaler: group.ctrl0.prescaler,
+                    channel_a_enabled: group.ctrl0.channel_a,
+                    channel_b_enabled: group.ctrl0.channel_b,
+                    frequency: group.ctrl1,
+                    channel_a: group.channel0,
+                    channel_b: group.channel1,
+                }
+            }),
+            dac: self.dac_configs.map(|dac| DacDebug {
+                enabled: dac.enabled(),
+                value: dac.value(),
+            }),
+            retain: data.retain.storage.clone(),
+        }
+    }
+
+    /// This function does the actual communication with the PiXtend board over SPI. Previous
+    /// commands are collected in a frame and then sent to the PiXtend board. The response is read
+    /// and stored for easy read access. Before sending a new command, an optional delay is
+    /// applied, if the last command was sent less than the cycle delay ago to conform with the
+    /// PiXtend documentation on timing. The cycle delay defaults to 30ms, see `set_cycle_delay`.
+    ///
+    /// This function can fail with a variety of errors, some of the most common ones are:
+    /// - `PiXtendError::NotReadyForCommunication`: The PiXtend board is i.e. in safe mode and
+    ///   not ready for communication, a restart is required
+    /// - `PiXtendError::InvalidSpiResponseLength`: The response from the PiXtend board didn't
+    ///   return the expected number of bytes even after the retries of
+    ///   `set_short_read_retries`, this is likely a wiring / connection issue
+    /// - `PiXtendError::InputCrcError`: The input data from the PiXtend board is corrupted
+    /// - `PiXtendError::PiXtendModelMismatch`: The connected PiXtend board is not the configured
+    ///   model
+    /// - `PiXtendError::UnsupportedModel`: The frame layout of the configured model is not
+    ///   supported yet
+    /// - `PiXtendError::OutputCrcError`: The output data sent to the PiXtend board is corrupted
+    /// - `PiXtendError::PwmAndDhtExclusive` or `PiXtendError::GpioPullupNotGloballyEnabled`: The
+    ///   staged configuration is inconsistent, see `validate_config`
+    pub fn read_write(&mut self) -> Result<(), PiXtendError> {
+        // Check that the configuration is consistent before transmitting it
+        self.validate_config()?;
+
+        // Check if the PiXtend board is ready
+        self.check_ready()?;
+
+        // Wait for the communication delay to be passed
+        self.wait_for_cycle_delay();
+
+        if self.skip_transfer()? {
+            self.skip_cycle();
+            return Ok(());
+        }
+
+        self.exchange()
+    }
+
+    /// Runs the given closure to stage the outputs and then performs one `read_write` cycle, so
+    /// the inputs of the response can be read afterwards. This keeps control loops in the
+    /// stage-then-exchange order, use `read_write` or `write` directly for finer control.
+    ///
+    /// Returns the same errors as `read_write`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pixtend::PiXtend;
+    /// # let mut pixtend = PiXtend::new().unwrap();
+    /// pixtend
+    ///     .cycle(|pixtend| pixtend.set_relay_output(0, true).unwrap())
+    ///     .unwrap();
+    /// let input = pixtend.get_digital_input(0).unwrap();
+    /// ```
+    pub fn cycle<F: FnOnce(&mut Self)>(&mut self, f: F) -> Result<(), PiXtendError> {
+        f(self);
+        self.read_write()
+    }
+
+    /// Non-blocking variant of `read_write`. If the last transfer was less than the cycle delay
+    /// ago, `Ok(false)` is returned immediately without any SPI communication. Otherwise the full
+    /// exchange of `read_write` is performed and `Ok(true)` is returned. This allows polling at a
+    /// custom cadence, i.e. from an event loop, without the sleep of `read_write`.
+    ///
+    /// Returns the same errors as `read_write`.
+    pub fn try_read_write(&mut self) -> Result<bool, PiXtendError> {
+        if !self.time_until_ready().is_zero() {
+            return Ok(false);
+        }
+
+        // Check that the configuration is consistent before transmitting it
+        self.validate_config()?;
+
+        // Check if the PiXtend board is ready
+        self.check_ready()?;
+
+        if self.skip_transfer()? {
+            self.skip_cycle();
+            return Ok(false);
+        }
+
+        self.exchange()?;
+        Ok(true)
+    }
+
+    /// Returns the remaining time until the cycle delay since the last transfer has passed, or
+    /// zero if it has already passed. This allows scheduling the next `try_read_write` with a
+    /// timer instead of polling it.
+    pub fn time_until_ready(&self) -> Duration {
+        self.cycle_delay.saturating_sub(self.last_read.elapsed())
+    }
+
+    /// Calls `read_write` and retries it if the error is recoverable, see
+    /// `PiXtendError::is_recoverable`, i.e. for intermittent CRC errors on long cabling. At most
+    /// `max_attempts` cycles are performed, a value of `0` is treated as `1`. The attempts are
+    /// spaced by the cycle delay of `read_write`. Short reads count as failed attempts instead
+    /// of being retried separately, see `set_short_read_retries`, so at most `max_attempts`
+    /// transfers are performed.
+    ///
+    /// Non-recoverable errors are returned immediately without retrying. If all attempts fail,
+    /// the error of the last attempt is returned.
+    pub fn read_write_retry(&mut self, max_attempts: usize) -> Result<(), PiXtendError> {
+        let short_read_retries = std::mem::replace(&mut self.short_read_retries, 0);
+        let mut attempt = 1;
+        let result = loop {
+            match self.read_write() {
+                Err(error) if error.is_recoverable() && attempt < max_attempts => {
+                    log_warn!("Cycle attempt {} failed, retrying: {}", attempt, error);
+                    attempt += 1;
+                }
+                result => break result,
+            }
+        };
+        self.short_read_retries = short_read_retries;
+        result
+    }
+
+    /// Async variant of `read_write` for applications running on a tokio runtime. The remaining
+    /// cycle delay is awaited with `tokio::time::sleep` instead of blocking the thread, and the
+    /// synchronous SPI transfers are run on the blocking pool via `tokio::task::spawn_blocking`,
+    /// so this works on both the current thread and the multi-threaded runtime.
+    ///
+    /// Only one of `read_write` and `read_write_async` should be used per instance, since both
+    /// share the timestamp of the last transfer.
+    ///
+    /// Returns the same errors as `read_write`.
+    #[cfg(feature = "async")]
+    pub async fn read_write_async(&mut self) -> Result<(), PiXtendError>
+    where
+        T: Send + 'static,
+    {
+        // Check that the configuration is consistent before transmitting it
+        self.validate_config()?;
+
+        // Check if the PiXtend board is ready
+        self.check_ready()?;
+
+        // Wait for the communication delay to be passed without blocking the executor
+        let remaining = self.time_until_ready();
+        if !remaining.is_zero() {
+            tokio::time::sleep(remaining).await;
+        }
+
+        if self.skip_transfer()? {
+            self.skip_cycle();
+            return Ok(());
+        }
+
+        let (output, payload) = self.prepare_output()?;
+
+        // Transfer the data on the blocking pool, a short read is retried like in `read_write`
+        let mut retries = 0;
+        let buffer = loop {
+            let spi_pixtend = Arc::clone(&self.spi_pixtend);
+            let frame = output.clone();
+            let (buffer, bytes_read): (Vec<u8>, usize) = run_blocking(move || {
+                let mut buffer = vec![0u8; frame.len()];
+                let bytes_read = lock(&spi_pixtend).transfer(&mut buffer, &frame)?;
+                Ok((buffer, bytes_read))
+            })
+            .await?;
+            if self.check_response(&output, &buffer, bytes_read, &mut retries)? {
+                break buffer;
+            }
+
+            tokio::time::sleep(self.time_until_ready()).await;
+        };
+
+        self.process_response(buffer)?;
+
+        // Write the two DAC values to the DAC SPI
+        if self.dac_enabled {
+            let spi_dac = Arc::clone(&self.spi_dac);
+            let frames = self.dac_frames()?;
+            run_blocking(move || {
+                let mut spi_dac = lock(&spi_dac);
+                for frame in frames {
+                    spi_dac.write(&frame)?;
+                }
+                Ok(())
+            })
+            .await?;
+        }
+
+        self.last_payload = payload;
+        Ok(())
+    }
+
+    /// Calculates the CRC values and serializes the output frame, which is returned together
+    /// with the payload compared by `set_skip_unchanged`
+    fn prepare_output(&mut self) -> Result<(Vec<u8>, Option<Vec<u8>>), PiXtendError> {
+        // Calculate the CRC values
+        self.output.update()?;
+        let payload = self.output_payload()?;
+
+        // The frame layout is only implemented for the PiXtend L, so the output frame must match
+        // the frame length of the configured model
+        let model = self.output.header.model;
+        let output = self.output.to_bytes()?;
+        if output.len() != model.frame_length() {
+            return Err(PiXtendError::UnsupportedModel(model));
+        }
+
+        Ok((output, payload))
+    }
+
+    /// Transfers the output frame, then parses and checks the response
+    fn exchange(&mut self) -> Result<(), PiXtendError> {
+        let (output, payload) = self.prepare_output()?;
+
+        // Transfer the data and read the response, a short read is retried after the cycle
+        // delay, since it is usually caused by a transient fault of the SPI link
+        let mut retries = 0;
+        let buffer = loop {
+            let mut buffer = vec![0u8; output.len()];
+            let bytes_read = lock(&self.spi_pixtend).transfer(&mut buffer, &output)?;
+            if self.check_response(&output, &buffer, bytes_read, &mut retries)? {
+                break buffer;
+            }
+
+            self.wait_for_cycle_delay();
+        };
+
+        self.process_response(buffer)?;
+
+        // Write the two DAC values to the DAC SPI
+        self.write_dac_values()?;
+        self.last_payload = payload;
+        Ok(())
+    }
+
+    /// Accounts one transfer and checks the length of its response. Returns `true` if the
+    /// response is complete and `false` if the short read should be retried, or an error once
+    /// the retries are exhausted.
+    fn check_response(
+        &mut self,
+        output: &[u8],
+        buffer: &[u8],
+        bytes_read: usize,
+        retries: &mut u32,
+    ) -> Result<bool, PiXtendError> {
+        self.last_read = Instant::now();
+        self.cycle_skipped = false;
+        self.stats.cycles += 1;
+        let response = &buffer[..bytes_read.min(buffer.len())];
+        log_trace!("TX {}", crate::logging::hex(output));
+        log_trace!("RX {}", crate::logging::hex(response));
+        self.record(output, response);
+        if bytes_read == buffer.len() {
+            return Ok(true);
+        }
+
+        log_error!(
+            "Received {} bytes from the PiXtend, expected {}",
+            bytes_read,
+            buffer.len()
+        );
+        self.stats.short_frames += 1;
+        if *retries >= self.short_read_retries {
+            return Err(PiXtendError::InvalidSpiResponseLength {
+                expected: buffer.len(),
+                actual: bytes_read,
+            });
+        }
+
+        *retries += 1;
+        Ok(false)
+    }
+
+    /// Parses and checks the response, then stores the input data
+    fn process_response(&mut self, buffer: Vec<u8>) -> Result<(), PiXtendError> {
+        let model = self.output.header.model;
+
+        // Parse the response
+        let (_, input) = Input::from_bytes((&buffer, 0))?;
+
+        // Check the input CRC
+        if !input.check_crc_valid() {
+            log_warn!("CRC check of the input frame failed");
+            self.stats.input_crc_errors += 1;
+            return Err(PiXtendError::InputCrcError);
+        }
+
+        // Check if the returned model matches the configured model
+        if input.header.model != model.byte() {
+            log_error!(
+                "PiXtend reported model {:?}, expected {:?}",
+                input.header.model as char,
+                model
+            );
+            self.stats.model_mismatches += 1;
+            return Err(PiXtendError::PiXtendModelMismatch);
+        }
+
+        // Latch the warnings, so that transient ones are not missed between polls
+        self.warning_latches.latch(input.header.warnings);
+
+        // Check if there is an error in the state
+        self.last_error_code = Some(input.header.state.error_code);
+        if input.header.state.error_code != ErrorCode::NoError {
+            log_error!(
+                "PiXtend reported error code {:?}",
+                input.header.state.error_code
+            );
+        }
+        match input.header.state.error_code {
+            ErrorCode::NoError => {}
+            ErrorCode::DataCrcError | ErrorCode::HeaderCrcError => {
+                self.stats.output_crc_errors += 1;
+                return Err(PiXtendError::OutputCrcError);
+            }
+            ErrorCode::DataBlockTooShort => {
+                self.stats.short_frames += 1;
+                return Err(PiXtendError::DataBlockTooShort);
+            }
+            ErrorCode::PiXtendModelMismatch => {
+                self.stats.model_mismatches += 1;
+                return Err(PiXtendError::PiXtendModelMismatch);
+            }
+            ErrorCode::SPIFrequencyTooHigh => return Err(PiXtendError::SPIFrequencyTooHigh),
+        }
+
+        // Count the consecutive identical input frames to detect a stalled link
+        self.identical_frames = match self.input_bytes.as_ref() == Some(&buffer) {
+            true => self.identical_frames.saturating_add(1),
+            false => 1,
+        };
+        if let Some(frames) = self.stall_detection {
+            if self.identical_frames >= frames {
+                log_error!(
+                    "Received {} identical input frames from the PiXtend",
+                    self.identical_frames
+                );
+                return Err(PiXtendError::LinkStalled(self.identical_frames));
+            }
+        }
+
+        log_debug!(
+            "Cycle successful, firmware version {}, hardware version {}",
+            input.header.firmware,
+            input.header.hardware
+        );
+
+        // Record the analog values for averaging and filtering
+        self.analog_average.push(input.data.analog_in.raw_values());
+        self.analog_filter.push(input.data.analog_in.raw_values());
+
+        // Count the consecutive cycles without sensor data to detect disconnected sensors
+        for (count, sensor) in self
+            .sensor_empty_cycles
+            .iter_mut()
+            .zip(input.data.sensor_in.sensors())
+        {
+            *count = match sensor.is_empty() {
+                true => count.saturating_add(1),
+                false => 0,
+            };
+        }
+
+        // Record the sensor readings for the smoothing
+        self.sensor_smoothing.push(input.data.sensor_in.sensors());
+
+        // Keep the digital inputs of the previous cycle for the edge detection
+        self.previous_digital_inputs = self
+            .input
+            .as_ref()
+            .map(|input| input.data.digital_in.as_mask());
+
+        // Store the input and the raw response for read access
+        self.input = Some(input);
+        self.input_bytes = Some(buffer);
+
+        // Measure the time since the previous successful cycle
+        let now = Instant::now();
+        self.last_cycle_duration = self.last_completion.map(|previous| now - previous);
+        self.last_completion = Some(now);
+        Ok(())
+    }
+
+    /// Returns the counters of performed cycles and communication errors since the creation of
+    /// the instance or the last call of `reset_stats`. The counters are not affected by `reset`.
+    pub fn stats(&self) -> CycleStats {
+        self.stats
+    }
+
+    /// Returns the time between the completion of the last two successful cycles of `read_write`,
+    /// or `None` if less than two cycles have been completed. In contrast to the nominal cycle
+    /// delay, this includes the SPI transfer and the processing of the application between the
+    /// cycles, i.e. to detect jitter in a control loop.
+    pub fn last_cycle_duration(&self) -> Option<Duration> {
+        self.last_cycle_duration
+    }
+
+    /// Resets all counters of the cycle statistics to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = CycleStats::default();
+    }
+
+    /// Sends the collected output frame to the PiXtend board without reading the response. This
+    /// is useful for pure output applications, because the input frame is neither parsed nor
+    /// CRC checked. The same cycle delay as for `read_write` is applied.
+    ///
+    /// Since no input data is stored, getters like `get_digital_input` keep returning
+    /// `PiXtendError::NoInputDataAvailable` until `read_write` is called.
+    pub fn write(&mut self) -> Result<(), PiXtendError> {
+        // Check that the configuration is consistent before transmitting it
+        self.validate_config()?;
+
+        // Check if the PiXtend board is ready
+        self.check_ready()?;
+
+        // Wait for the communication delay to be passed
+        self.wait_for_cycle_delay();
+
+        if self.skip_transfer()? {
+            self.skip_cycle();
+            return Ok(());
+        }
+
+        self.transmit_output()
+    }
+
+    /// Sends the output frame without reading the response and without validating the
+    /// configuration, see `write`
+    fn write_frame(&mut self) -> Result<(), PiXtendError> {
+        // Check if the PiXtend board is ready
+        self.check_ready()?;
+
+        // Wait for the communication delay to be passed
+        self.wait_for_cycle_delay();
+
+        self.transmit_output()
+    }
+
+    /// Sends the output frame immediately, bypassing the cycle delay. This is meant for
+    /// safety-critical writes like an emergency stop after `clear_outputs`, where waiting for the
+    /// cycle delay is not acceptable. Like `write`, the response is not read, and the time of the
+    /// transfer is recorded, so the next cycle waits for the full cycle delay again.
+    ///
+    /// Transfers in quicker succession than the timing of the PiXtend documentation allows can
+    /// cause the firmware to report `PiXtendError::SPIFrequencyTooHigh` or CRC errors in the
+    /// following cycles, so this should be used sparingly.
+    ///
+    /// Returns the same errors as `write`.
+    pub fn flush(&mut self) -> Result<(), PiXtendError> {
+        // Check that the configuration is consistent before transmitting it
+        self.validate_config()?;
+
+        // Check if the PiXtend board is ready
+        self.check_ready()?;
+
+        self.transmit_output()
+    }
+
+    /// Transfers the output frame without reading the response and writes the DAC values
+    fn transmit_output(&mut self) -> Result<(), PiXtendError> {
+        let (output, payload) = self.prepare_output()?;
+
+        // Transfer the data without reading the response
+        log_trace!("TX {}", crate::logging::hex(&output));
+        lock(&self.spi_pixtend).write(&output)?;
+        self.last_read = Instant::now();
+        self.cycle_skipped = false;
+        self.record(&output, &[]);
+
+        // Write the two DAC values to the DAC SPI
+        self.write_dac_values()?;
+        self.last_payload = payload;
+        Ok(())
+    }
+
+    /// Returns the bytes that determine the transmitted outputs, i.e. the output frame without
+    /// the derived CRC fields and the DAC words, or `None` if `set_skip_unchanged` is disabled
+    fn output_payload(&self) -> Result<Option<Vec<u8>>, PiXtendError> {
+        if !self.skip_unchanged {
+            return Ok(None);
+        }
+
+        let mut payload = self.output.header.to_bytes()?;
+        payload.extend(self.output.data.to_bytes()?);
+        if self.dac_enabled {
+            for dac in self.dac_configs {
+                payload.extend(dac.to_bytes()?);
+            }
+        }
+
+        Ok(Some(payload))
+    }
+
+    /// Returns whether the transfer can be skipped, since `set_skip_unchanged` is enabled and the
+    /// outputs didn't change since the last transmitted frame
+    fn skip_transfer(&self) -> Result<bool, PiXtendError> {
+        match (&self.last_payload, self.output_payload()?) {
+            (Some(last), Some(payload)) => Ok(*last == payload),
+            _ => Ok(false),
+        }
+    }
+
+    /// Marks the current cycle as skipped, so the next one is again spaced by the cycle delay
+    fn skip_cycle(&mut self) {
+        self.last_read = Instant::now();
+        self.cycle_skipped = true;
+    }
+
+    /// Returns an error if the last response reported that the microcontroller is not running
+    fn check_ready(&self) -> Result<(), PiXtendError> {
+        if let Some(input) = &self.input {
+            if !input.header.state.run {
+                return Err(PiXtendError::NotReadyForCommunication);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converts a debounce time in milliseconds to cycles of the cycle delay. Without a cycle
+    /// delay the duration of a cycle is unknown, so no time can be represented.
+    fn debounce_cycles(&self, ms: u32) -> Result<u8, PiXtendError> {
+        let cycle_us = self.cycle_delay.as_micros();
+        if cycle_us == 0 {
+            return Err(PiXtendError::DebounceTimeOutOfRange(ms));
+        }
+
+        u8::try_from(u128::from(ms) * 1000 / cycle_us)
+            .map_err(|_| PiXtendError::DebounceTimeOutOfRange(ms))
+    }
+
+    /// Sleeps until the communication delay since the last transfer has passed
+    fn wait_for_cycle_delay(&self) {
+        let remaining = self.time_until_ready();
+        if !remaining.is_zero() {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    /// Writes the two DAC values to the DAC SPI, unless the DAC is disabled
+    fn write_dac_values(&mut self) -> Result<(), PiXtendError> {
+        if !self.dac_enabled {
+            return Ok(());
+        }
+
+        self.write_dac()
+    }
+
+    /// Switches off all digital outputs, relays and PWM channels, i.e. for a software emergency
+    /// stop. In contrast to `reset`, the GPIO, PWM, debounce, retain and system configurations
+    /// as well as the stored input data are kept. The PWM groups keep their mode and prescaler,
+    /// only the channel values are set to zero. The outputs are switched off with the next
+    /// `read_write` or `write`.
+    pub fn clear_outputs(&mut self) {
+        self.output.data.digital_out.clear();
+        self.output.data.relay_out.clear();
+        self.output.data.pwm.clear();
+    }
+
+    /// Clears the transient state after an error while keeping the configuration. In contrast to
+    /// `reset`, which restores the defaults of everything, the GPIO and PWM configurations, the
+    /// header and system settings as well as the GPIO and PWM control registers are kept. The
+    /// digital and relay outputs, the PWM channel values and the GPIO outputs are switched off,
+    /// the DACs are disabled and the stored input data, edges, latched warnings and the histories
+    /// of the averaging, filtering and sensor smoothing are cleared. The filter weights, the
+    /// smoothing sizes and the retain data are kept.
+    pub fn soft_reset(&mut self) {
+        self.clear_outputs();
+        for (index, config) in (0..).zip(self.gpio_configs) {
+            // The GPIO output register holds the pullup resistors of the inputs
+            if config == GpioConfig::Output {
+                let _ = self.output.data.gpio_out.set_gpio_output(index, false);
+            }
+        }
+        self.dac_configs = [Dac::disabled(Channel::A), Dac::disabled(Channel::B)];
+        self.input = None;
+        self.last_payload = None;
+        self.input_bytes = None;
+        self.identical_frames = 0;
+        self.previous_digital_inputs = None;
+        self.last_error_code = None;
+        self.warning_latches = Warnings::default();
+        self.analog_average.clear();
+        self.analog_filter.clear();
+        self.sensor_empty_cycles = [0; 4];
+        self.sensor_smoothing.clear();
+    }
+
+    /// Resets the PiXtend instance to its default state. This includes resetting the output,
+    /// input, GPIO configurations and PWM configurations, see `soft_reset` to keep the
+    /// configuration.
+    pub fn reset(&mut self) {
+        let model = self.output.header.model;
+        self.output = Output::default();
+        self.output.header.model = model;
+        self.input = None;
+        self.last_payload = None;
+        self.input_bytes = None;
+        self.identical_frames = 0;
+        self.previous_digital_inputs = None;
+        self.last_error_code = None;
+        self.warning_latches = Warnings::default();
+        self.analog_average.clear();
+        self.analog_filter = AnalogFilter::default();
+        self.gpio_configs = [GpioConfig::default(); 4];
+        self.pwm_configs = [PwmConfig::default(); 3];
+        self.servo_ranges = [DEFAULT_SERVO_RANGE; 3];
+        self.servo_pulse_widths = [SERVO_PULSE_WINDOW_US; 3];
+        self.current_calibrations = [DEFAULT_CURRENT_CALIBRATION; 2];
+        self.reference_voltages = [ReferenceVoltage::V10; 4];
+        self.sensor_empty_cycles = [0; 4];
+        self.sensor_timeout_cycles = DEFAULT_SENSOR_TIMEOUT_CYCLES;
+        self.sensor_smoothing = SensorSmoothing::default();
+        self.dac_configs = [Dac::disabled(Channel::A), Dac::disabled(Channel::B)];
+    }
+}
+
+impl<T: SpiTransport + Default> Default for PiXtend<T> {
+    /// Creates a PiXtend instance from the default transports, see `new_with_transport`
+    fn default() -> Self {
+        Self::new_with_transport(T::default(), T::default())
+    }
+}
+
+impl<T: SpiTransport> Drop for PiXtend<T> {
+    fn drop(&mut self) {
+        if !self.reset_on_drop {
+            return;
+        }
+
+        // Send a final frame requesting the safe state, errors can't be reported during drop
+        // The configuration is not validated, so the frame is also sent for an inconsistent one
+        self.enable_safe_mode();
+        let _ = self.write_frame();
+
+        // Disable the communication with the PiXtend board
+        if let Some(enable_pin) = &mut self.enable_pin {
+            enable_pin.set_low();
+        }
+    }
+}
+
+/// Runs a blocking SPI operation on the blocking pool of tokio
+#[cfg(feature = "async")]
+async fn run_blocking<R, F>(f: F) -> Result<R, PiXtendError>
+where
+    R: Send + 'static,
+    F: FnOnce() -> Result<R, PiXtendError> + Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        // The task is only cancelled on shutdown of the runtime, which drops this future as well
+        Err(error) => std::panic::resume_unwind(error.into_panic()),
+    }
+}
+
+/// Checks that the prescaler is active and the resulting frequency doesn't exceed 20kHz
+fn check_pwm_frequency(index: u8, prescaler: PwmPrescaler, value: u16) -> Result<(), PiXtendError> {
+    match pwm_config::frequency_hz(prescaler, value) {
+        None => Err(PiXtendError::PwmPrescalerDeactivated(index)),
+        Some(hz) if hz > pwm_config::PWM_MAX_FREQUENCY_HZ => {
+            Err(PiXtendError::PwmFrequencyOutOfRange { index, hz })
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+#[test]
+fn test_read_write_digital_inputs() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0u8; 100];
+    data[0] = 0b1000_0001;
+    data[1] = 0b0000_0010;
+    let frame = MockTransport::input_frame([5, 21, b'L', 0b0000_0001, 0, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    pixtend.read_write().unwrap();
+
+    assert_eq!(pixtend.get_firmware_version().unwrap(), 5);
+    assert_eq!(pixtend.get_hardware_version().unwrap(), 21);
+    assert_eq!(
+        pixtend.get_hardware_revision().unwrap(),
+        HardwareRevision::Version { major: 2, minor: 1 }
+    );
+    assert_eq!(pixtend.get_digital_input(0).unwrap(), true);
+    assert_eq!(pixtend.get_digital_input(1).unwrap(), false);
+    assert_eq!(pixtend.get_digital_input(7).unwrap(), true);
+    assert_eq!(pixtend.get_digital_input(9).unwrap(), true);
+    assert_eq!(pixtend.get_digital_input(8).unwrap(), false);
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written[0].len(), 111);
+    assert_eq!(pixtend.spi_dac.lock().unwrap().written.len(), 2);
+}
+
+#[test]
+fn test_is_running() {
+    use crate::transport::MockTransport;
+
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]),
+        MockTransport::input_frame([0, 0, b'L', 0b0000_0000, 0, 0, 0], [0; 100]),
+    ]);
+    assert!(matches!(
+        pixtend.is_running(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.is_running().unwrap(), true);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.is_running().unwrap(), false);
+}
+
+#[test]
+fn test_last_frame_bytes() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::mocked([frame.clone()]);
+    assert_eq!(pixtend.last_input_bytes(), None);
+
+    pixtend.read_write().unwrap();
+    assert_eq!(
+        pixtend.last_output_bytes().unwrap(),
+        pixtend.spi_pixtend.lock().unwrap().written[0]
+    );
+    assert_eq!(pixtend.last_input_bytes().unwrap(), frame);
+}
+
+#[test]
+fn test_analog_raw() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0u8; 100];
+    data[4..6].copy_from_slice(&1023u16.to_le_bytes());
+    data[12..14].copy_from_slice(&256u16.to_le_bytes());
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    pixtend.read_write().unwrap();
+
+    assert_eq!(pixtend.get_analog_raw(0).unwrap(), 0);
+    assert_eq!(pixtend.get_analog_raw(1).unwrap(), 1023);
+    assert_eq!(pixtend.get_analog_raw(5).unwrap(), 256);
+    assert!(pixtend.get_analog_raw(6).is_err());
+}
+
+#[test]
+fn test_digital_inputs_mask() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0u8; 100];
+    data[0] = 0b1000_0001;
+    data[1] = 0b0000_0010;
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.get_digital_inputs(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.get_digital_inputs().unwrap(), 0b0000_0010_1000_0001);
+}
+
+#[test]
+fn test_versions() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([5, 21, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.get_versions(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    assert_eq!(
+        pixtend.get_versions().unwrap(),
+        Versions {
+            firmware: 5,
+            hardware: 21,
+            model: 'L'
+        }
+    );
+}
+
+#[test]
+fn test_digital_input_edges() {
+    use crate::transport::MockTransport;
+
+    let mut first = [0u8; 100];
+    first[0] = 0b0000_0011;
+    let mut second = [0u8; 100];
+    second[0] = 0b0000_0110;
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, first),
+        MockTransport::input_frame(header, second),
+    ]);
+    assert!(pixtend.rising_edges().is_err());
+
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.rising_edges().unwrap(), 0);
+    assert_eq!(pixtend.falling_edges().unwrap(), 0);
+
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.rising_edges().unwrap(), 0b0000_0100);
+    assert_eq!(pixtend.falling_edges().unwrap(), 0b0000_0001);
+
+    pixtend.reset();
+    assert!(pixtend.falling_edges().is_err());
+}
+
+#[test]
+fn test_safe_mode() {
+    let mut pixtend = PiXtend::mocked([]);
+    assert_eq!(pixtend.is_in_safe_mode(), false);
+    pixtend.enable_safe_mode();
+    assert_eq!(pixtend.is_in_safe_mode(), true);
+    pixtend.clear_safe_mode();
+    assert_eq!(pixtend.is_in_safe_mode(), false);
+}
+
+#[test]
+fn test_try_read_write() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::mocked([frame]);
+    pixtend.set_cycle_delay(Duration::from_secs(60));
+    assert!(pixtend.time_until_ready() > Duration::from_secs(59));
+    assert_eq!(pixtend.try_read_write().unwrap(), false);
+    assert!(pixtend.spi_pixtend.lock().unwrap().written.is_empty());
+
+    pixtend.set_cycle_delay(Duration::ZERO);
+    assert_eq!(pixtend.time_until_ready(), Duration::ZERO);
+    assert_eq!(pixtend.try_read_write().unwrap(), true);
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 1);
+    assert_eq!(pixtend.is_running().unwrap(), true);
+}
+
+#[cfg(all(test, feature = "async"))]
+#[tokio::test]
+async fn test_read_write_async() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::mocked([frame]);
+    pixtend.read_write_async().await.unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 1);
+    assert_eq!(pixtend.is_running().unwrap(), true);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_retain_typed() {
+    use crate::transport::MockTransport;
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct State {
+        counter: u32,
+        setpoint: f32,
+    }
+
+    let mut pixtend = PiXtend::mocked([]);
+    let state = State {
+        counter: 1337,
+        setpoint: 21.5,
+    };
+    assert!(matches!(
+        pixtend.set_retain_typed(&state),
+        Err(PiXtendError::RetainDataNotGloballyEnabled)
+    ));
+    pixtend.set_retain_enable(true);
+    pixtend.set_retain_typed(&state).unwrap();
+    assert!(matches!(
+        pixtend.set_retain_typed(&vec![0u8; 64]),
+        Err(PiXtendError::RetainDataTooLarge(_))
+    ));
+
+    // Loop the staged retain data back as the input retain data
+    let mut data = [0u8; 100];
+    data[36..].copy_from_slice(&pixtend.output.data.retain.storage);
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], data);
+    pixtend
+        .spi_pixtend
+        .lock()
+        .unwrap()
+        .responses
+        .push_back(frame);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.get_retain_typed::<State>().unwrap(), state);
+}
+
+#[test]
+fn test_reset_on_drop() {
+    use std::{cell::RefCell, rc::Rc};
+
+    // Transport which shares the written frames, so they can be inspected after the drop
+    struct SharedTransport(Rc<RefCell<Vec<Vec<u8>>>>);
+
+    impl SpiTransport for SharedTransport {
+        fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<usize, PiXtendError> {
+            Ok(0)
+        }
+
+        fn write(&mut self, data: &[u8]) -> Result<(), PiXtendError> {
+            self.0.borrow_mut().push(data.to_vec());
+            Ok(())
+        }
+    }
+
+    let written = Rc::new(RefCell::new(Vec::new()));
+    let pixtend = PiXtend::from_transports(
+        SharedTransport(written.clone()),
+        SharedTransport(Rc::default()),
+    );
+    drop(pixtend);
+    assert!(written.borrow().is_empty());
+
+    let mut pixtend = PiXtend::from_transports(
+        SharedTransport(written.clone()),
+        SharedTransport(Rc::default()),
+    );
+    pixtend.set_reset_on_drop(true);
+    pixtend.set_cycle_delay(Duration::ZERO);
+    drop(pixtend);
+    assert_eq!(written.borrow().len(), 1);
+}
+
+#[test]
+fn test_clear_outputs() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_digital_output(3, true).unwrap();
+    pixtend.set_relay_output(1, true).unwrap();
+    pixtend.set_gpio_config(0, GpioConfig::Output).unwrap();
+    pixtend
+        .set_pwm_config(
+            0,
+            PwmConfig::for_frequency(1000.0, PwmMode::DutyCycle).unwrap(),
+        )
+        .unwrap();
+    pixtend.set_pwm_duty_cycle(0, Channel::A, 100).unwrap();
+    pixtend.clear_outputs();
+
+    assert_eq!(pixtend.get_digital_output(3).unwrap(), false);
+    assert_eq!(pixtend.get_relay_output(1).unwrap(), false);
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 0);
+    assert_eq!(
+        pixtend.output.data.pwm.group0.ctrl0.mode,
+        PwmMode::DutyCycle
+    );
+    assert_eq!(pixtend.gpio_configs[0], GpioConfig::Output);
+}
+
+#[test]
+fn test_current_calibration() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0u8; 100];
+    data[10..12].copy_from_slice(&992u16.to_le_bytes());
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    pixtend.read_write().unwrap();
+    let milliamperes = pixtend.get_analog_current_input(4).unwrap();
+    assert!((milliamperes - 992.0 * 0.020158400229358).abs() < 1e-9);
+
+    pixtend.set_current_calibration(4, 0.5, -4.0).unwrap();
+    assert_eq!(pixtend.get_analog_current_input(4).unwrap(), 492.0);
+    assert_eq!(pixtend.get_analog_current_input_averaged(4).unwrap(), 492.0);
+    assert!(pixtend.set_current_calibration(3, 1.0, 0.0).is_err());
+}
+
+#[test]
+fn test_read_write_unsupported_model() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.output.header.model = Model::SModel;
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::UnsupportedModel(Model::SModel))
+    ));
+    assert!(pixtend.spi_pixtend.lock().unwrap().written.is_empty());
+}
+
+#[test]
+fn test_config() {
+    let config = PiXtendConfig {
+        watchdog: Watchdog::Activated1s,
+        retain_enable: true,
+        gpio_pullup_enable: true,
+        digital_debounce: [1, 2, 3, 4, 5, 6, 7, 8],
+        gpio_debounce: [9, 10],
+        gpio_configs: [
+            GpioConfig::Output,
+            GpioConfig::Input(true),
+            GpioConfig::Input(false),
+            GpioConfig::Output,
+        ],
+        pwm_configs: [
+            PwmConfig::Servo {
+                channel_a: true,
+                channel_b: false,
+            },
+            PwmConfig::Deactivated,
+            PwmConfig::Deactivated,
+        ],
+        ..Default::default()
+    };
+
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.apply_config(&config).unwrap();
+    assert_eq!(pixtend.get_config(), config);
+
+    // Switching from PWM to a sensor configuration must not conflict with the old state
+    let sensor_config = PiXtendConfig {
+        gpio_configs: [GpioConfig::Sensor; 4],
+        ..Default::default()
+    };
+    pixtend.apply_config(&sensor_config).unwrap();
+    assert_eq!(pixtend.get_config(), sensor_config);
+    pixtend.apply_config(&config).unwrap();
+    assert_eq!(pixtend.get_config(), config);
+}
+
+#[test]
+fn test_pwm_frequency_range() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend
+        .set_pwm_config(
+            0,
+            PwmConfig::Frequency {
+                prescaler: PwmPrescaler::Prescale16MHz,
+                channel_a: true,
+                channel_b: true,
+            },
+        )
+        .unwrap();
+    pixtend.set_pwm_frequency(0, Channel::A, 400).unwrap();
+    assert!(matches!(
+        pixtend.set_pwm_frequency(0, Channel::B, 399),
+        Err(PiXtendError::PwmFrequencyOutOfRange { index: 0, .. })
+    ));
+    assert!(pixtend.set_pwm_frequency(0, Channel::B, 0).is_err());
+
+    pixtend
+        .set_pwm_config(
+            1,
+            PwmConfig::DutyCycle {
+                prescaler: PwmPrescaler::Prescale2MHz,
+                frequency: 10,
+                channel_a: true,
+                channel_b: true,
+            },
+        )
+        .unwrap();
+    assert!(matches!(
+        pixtend.set_pwm_duty_cycle(1, Channel::A, 5),
+        Err(PiXtendError::PwmFrequencyOutOfRange { index: 1, .. })
+    ));
+}
+
+#[test]
+fn test_pwm_duty_cycle_percent() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend
+        .set_pwm_config(
+            0,
+            PwmConfig::Universal {
+                prescaler: PwmPrescaler::Prescale62_5kHz,
+                frequency: 31250,
+                duty_cycle: 0,
+                channel_a: true,
+                channel_b: true,
+            },
+        )
+        .unwrap();
+    pixtend
+        .set_pwm_duty_cycle_percent(0, Channel::A, 50.0)
+        .unwrap();
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 15625);
+    pixtend
+        .set_pwm_duty_cycle_percent(0, Channel::A, 150.0)
+        .unwrap();
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 31250);
+    assert!(matches!(
+        pixtend.set_pwm_duty_cycle_percent(0, Channel::B, 50.0),
+        Err(PiXtendError::PwmChannelNotSettable {
+            index: 0,
+            channel: Channel::B
+        })
+    ));
+    assert!(matches!(
+        pixtend.set_pwm_duty_cycle_percent(1, Channel::A, 50.0),
+        Err(PiXtendError::PwmNotConfiguredForDutyCycle(1))
+    ));
+    assert!(matches!(
+        pixtend.set_pwm_duty_cycle_percent(3, Channel::A, 50.0),
+        Err(PiXtendError::InvalidPwmOutputGroupIndex(3))
+    ));
+}
+
+#[test]
+fn test_pwm_servo_angle() {
+    let mut pixtend = PiXtend::mocked([]);
+    let servo = PwmConfig::Servo {
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(0, servo).unwrap();
+    pixtend.set_pwm_servo_angle(0, Channel::A, 90.0).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 8000);
+    pixtend.set_pwm_servo_angle(0, Channel::B, 200.0).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group0.channel1, 16000);
+
+    pixtend.set_servo_range(0, -135.0, 135.0).unwrap();
+    pixtend.set_pwm_servo_angle(0, Channel::A, -135.0).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 0);
+    pixtend.set_pwm_servo_angle(0, Channel::A, 67.5).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 12000);
+
+    assert!(pixtend.set_servo_range(0, 90.0, 90.0).is_err());
+    assert!(matches!(
+        pixtend.set_pwm_servo_angle(1, Channel::A, 90.0),
+        Err(PiXtendError::PwmNotConfiguredAsServo(1))
+    ));
+
+    assert!(pixtend.set_pwm_servo(0, Channel::A, 16000).is_ok());
+    assert!(matches!(
+        pixtend.set_pwm_servo(0, Channel::A, 16001),
+        Err(PiXtendError::PwmServoValueOutOfRange {
+            index: 0,
+            value: 16001
+        })
+    ));
+}
+
+#[test]
+fn test_gpio_sensor_not_configured() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_gpio_config(1, GpioConfig::Output).unwrap();
+    assert!(matches!(
+        pixtend.get_gpio_temperature(0, SensorKind::DHT22),
+        Err(PiXtendError::GpioNotConfiguredAsSensor(0))
+    ));
+    assert!(matches!(
+        pixtend.get_gpio_humidity(1, SensorKind::DHT22),
+        Err(PiXtendError::GpioNotConfiguredAsSensor(1))
+    ));
+}
+
+#[test]
+fn test_sensor_not_responding() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut data = [0u8; 100];
+    data[15..17].copy_from_slice(&215u16.to_le_bytes());
+    data[17..19].copy_from_slice(&500u16.to_le_bytes());
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, [0; 100]),
+        MockTransport::input_frame(header, [0; 100]),
+        MockTransport::input_frame(header, data),
+    ]);
+    pixtend.set_gpio_config(0, GpioConfig::Sensor).unwrap();
+    pixtend.set_sensor_timeout_cycles(2);
+
+    pixtend.read_write().unwrap();
+    assert_eq!(
+        pixtend.get_gpio_temperature(0, SensorKind::DHT22).unwrap(),
+        0.0
+    );
+    pixtend.read_write().unwrap();
+    assert!(matches!(
+        pixtend.get_gpio_humidity(0, SensorKind::DHT22),
+        Err(PiXtendError::SensorNotResponding { index: 0 })
+    ));
+    pixtend.read_write().unwrap();
+    assert_eq!(
+        pixtend.get_gpio_temperature(0, SensorKind::DHT22).unwrap(),
+        21.5
+    );
+}
+
+#[test]
+fn test_output_readback() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_digital_output(11, true).unwrap();
+    pixtend.set_relay_output(2, true).unwrap();
+    pixtend.set_gpio_config(3, GpioConfig::Output).unwrap();
+    pixtend.set_gpio_output(3, true).unwrap();
+
+    assert_eq!(pixtend.get_digital_output(11).unwrap(), true);
+    assert_eq!(pixtend.get_digital_output(10).unwrap(), false);
+    assert_eq!(pixtend.get_relay_output(2).unwrap(), true);
+    assert_eq!(pixtend.get_gpio_output(3).unwrap(), true);
+    assert!(matches!(
+        pixtend.get_gpio_output(0),
+        Err(PiXtendError::GpioNotConfiguredAsOutput(0))
+    ));
+    assert!(pixtend.get_digital_output(12).is_err());
+    assert!(pixtend.get_relay_output(4).is_err());
+}
+
+#[test]
+fn test_read_write_errors() {
+    use crate::transport::MockTransport;
+
+    let mut frame = MockTransport::input_frame([5, 21, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    frame[20] ^= 0xFF;
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::InputCrcError)
+    ));
+    assert!(matches!(
+        pixtend.get_digital_input(0),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    let frame = MockTransport::input_frame([5, 21, b'S', 0b0000_0001, 0, 0, 0], [0; 100]);
+    pixtend
+        .spi_pixtend
+        .lock()
+        .unwrap()
+        .responses
+        .push_back(frame);
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::PiXtendModelMismatch)
+    ));
+
+    let frame = MockTransport::input_frame([5, 21, b'L', 0b0010_0001, 0, 0, 0], [0; 100]);
+    pixtend
+        .spi_pixtend
+        .lock()
+        .unwrap()
+        .responses
+        .push_back(frame);
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::OutputCrcError)
+    ));
+    assert_eq!(pixtend.last_error_code().unwrap(), ErrorCode::DataCrcError);
+
+    pixtend
+        .spi_pixtend
+        .lock()
+        .unwrap()
+        .responses
+        .push_back(vec![0; 50]);
+    pixtend
+        .spi_pixtend
+        .lock()
+        .unwrap()
+        .responses
+        .push_back(vec![0; 60]);
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::InvalidSpiResponseLength {
+            expected: 111,
+            actual: 60
+        })
+    ));
+}
+
+#[test]
+fn test_cycle_stats() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut input_crc = MockTransport::input_frame(header, [0; 100]);
+    input_crc[20] ^= 0xFF;
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, [0; 100]),
+        input_crc,
+        MockTransport::input_frame([0, 0, b'S', 0b0000_0001, 0, 0, 0], [0; 100]),
+        MockTransport::input_frame([0, 0, b'L', 0b0010_0001, 0, 0, 0], [0; 100]),
+        vec![0; 50],
+    ]);
+    pixtend.set_short_read_retries(0);
+    pixtend.read_write().unwrap();
+    for _ in 0..4 {
+        assert!(pixtend.read_write().is_err());
+    }
+
+    let stats = pixtend.stats();
+    assert_eq!(stats.cycles, 5);
+    assert_eq!(stats.input_crc_errors, 1);
+    assert_eq!(stats.model_mismatches, 1);
+    assert_eq!(stats.output_crc_errors, 1);
+    assert_eq!(stats.short_frames, 1);
+    pixtend.reset_stats();
+    assert_eq!(pixtend.stats(), CycleStats::default());
+}
+
+#[test]
+fn test_snapshot() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0u8; 100];
+    data[0] = 0b1000_0001;
+    data[2..4].copy_from_slice(&512u16.to_le_bytes());
+    data[12..14].copy_from_slice(&100u16.to_le_bytes());
+    data[14] = 0b0000_0101;
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0b0000_0100, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.snapshot(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    pixtend.set_current_calibration(5, 0.5, 1.0).unwrap();
+    let snapshot = pixtend.snapshot().unwrap();
+    assert_eq!(snapshot.digital_inputs, 0b1000_0001);
+    assert_eq!(snapshot.analog_voltages, [5.0, 0.0, 0.0, 0.0]);
+    assert_eq!(snapshot.analog_currents, [0.0, 51.0]);
+    assert_eq!(snapshot.gpio_inputs, [true, false, true, false]);
+    assert_eq!(snapshot.warnings.voltage_error, true);
+
+    pixtend
+        .set_reference_voltage(0, ReferenceVoltage::V5)
+        .unwrap();
+    assert_eq!(pixtend.snapshot().unwrap().analog_voltages[0], 2.5);
+    assert_eq!(pixtend.get_analog_voltage_input_averaged(0).unwrap(), 2.5);
+    assert_eq!(pixtend.get_analog_voltage_input_filtered(0).unwrap(), 2.5);
+}
+
+#[test]
+fn test_new_with_transport() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::new_with_transport(
+        MockTransport::with_responses([frame]),
+        MockTransport::default(),
+    );
+    assert!(pixtend.enable_pin.is_none());
+    pixtend.set_digital_output(0, true).unwrap();
+    pixtend.read_write().unwrap();
+    assert_eq!(
+        pixtend.spi_pixtend.lock().unwrap().written[0],
+        pixtend.last_output_bytes().unwrap()
+    );
+    assert_eq!(pixtend.is_running().unwrap(), true);
+
+    let mut pixtend = PiXtend::<MockTransport>::default();
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::InvalidSpiResponseLength {
+            expected: 111,
+            actual: 0
+        })
+    ));
+}
+
+#[test]
+fn test_pwm_frequency_hz() {
+    let mut pixtend = PiXtend::mocked([]);
+    let universal = PwmConfig::for_frequency(1.0, PwmMode::Universal).unwrap();
+    pixtend.set_pwm_config(0, universal).unwrap();
+    assert_eq!(pixtend.get_pwm_frequency_hz(0, Channel::A).unwrap(), 1.0);
+    assert_eq!(pixtend.get_pwm_frequency_hz(0, Channel::B).unwrap(), 0.5);
+
+    let frequency = PwmConfig::for_frequency(1000.0, PwmMode::Frequency).unwrap();
+    pixtend.set_pwm_config(1, frequency).unwrap();
+    assert_eq!(pixtend.get_pwm_frequency_hz(1, Channel::B).unwrap(), 0.0);
+    pixtend.set_pwm_frequency(1, Channel::B, 8000).unwrap();
+    assert_eq!(pixtend.get_pwm_frequency_hz(1, Channel::B).unwrap(), 1000.0);
+
+    assert!(matches!(
+        pixtend.get_pwm_frequency_hz(2, Channel::A),
+        Err(PiXtendError::PwmFrequencyNotConfigured(2))
+    ));
+    assert!(matches!(
+        pixtend.get_pwm_frequency_hz(3, Channel::A),
+        Err(PiXtendError::InvalidPwmOutputGroupIndex(3))
+    ));
+}
+
+#[test]
+fn test_led_meaning() {
+    use crate::transport::MockTransport;
+
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]),
+        MockTransport::input_frame([0, 0, b'L', 0b0110_0001, 0, 0, 0], [0; 100]),
+        MockTransport::input_frame([0, 0, b'L', 0b0000_0000, 0, 0, 0], [0; 100]),
+    ]);
+    assert!(matches!(
+        pixtend.led_meaning(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.led_meaning().unwrap(), LedState::Running);
+    assert!(pixtend.read_write().is_err());
+    assert_eq!(
+        pixtend.led_meaning().unwrap(),
+        LedState::Error(ErrorCode::SPIFrequencyTooHigh)
+    );
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.led_meaning().unwrap(), LedState::SafeState);
+    pixtend.set_led_disable(true);
+    assert_eq!(pixtend.led_meaning().unwrap(), LedState::Disabled);
+}
+
+#[test]
+fn test_validate_config() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.validate_config().unwrap();
+    pixtend.set_gpio_pullup_enable(true);
+    pixtend.set_gpio_config(0, GpioConfig::Input(true)).unwrap();
+    pixtend.validate_config().unwrap();
+
+    pixtend.set_gpio_pullup_enable(false);
+    assert!(matches!(
+        pixtend.validate_config(),
+        Err(PiXtendError::GpioPullupNotGloballyEnabled)
+    ));
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::GpioPullupNotGloballyEnabled)
+    ));
+    assert!(pixtend.write().is_err());
+    assert!(pixtend.spi_pixtend.lock().unwrap().written.is_empty());
+}
+
+#[test]
+fn test_analog_filter() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut first = [0u8; 100];
+    first[2..4].copy_from_slice(&512u16.to_le_bytes());
+    first[10..12].copy_from_slice(&400u16.to_le_bytes());
+    let mut second = [0u8; 100];
+    second[2..4].copy_from_slice(&256u16.to_le_bytes());
+    second[10..12].copy_from_slice(&200u16.to_le_bytes());
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, first),
+        MockTransport::input_frame(header, second),
+    ]);
+    pixtend.set_analog_filter(0, 0.5).unwrap();
+    pixtend.set_analog_filter(4, 0.5).unwrap();
+    pixtend.set_current_calibration(4, 0.01, 0.0).unwrap();
+    assert!(pixtend.set_analog_filter(0, 1.5).is_err());
+    assert!(pixtend.set_analog_filter(6, 0.5).is_err());
+    assert!(matches!(
+        pixtend.get_analog_voltage_input_filtered(0),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    let voltage = pixtend.get_analog_voltage_input_filtered(0);
+    assert_eq!(voltage.unwrap(), 5.0);
+    pixtend.read_write().unwrap();
+    let voltage = pixtend.get_analog_voltage_input_filtered(0);
+    assert_eq!(voltage.unwrap(), 3.75);
+    let current = pixtend.get_analog_current_input_filtered(4).unwrap();
+    assert!((current - 3.0).abs() < 1e-9);
+    assert!(matches!(
+        pixtend.get_analog_current_input_filtered(3),
+        Err(PiXtendError::InvalidAnalogCurrentInputIndex(3))
+    ));
+
+    pixtend.reset();
+    assert!(pixtend.get_analog_voltage_input_filtered(0).is_err());
+}
+
+#[test]
+fn test_read_write_retry() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([
+        vec![0; 50],
+        vec![0; 50],
+        MockTransport::input_frame(header, [0; 100]),
+        vec![0; 50],
+        vec![0; 50],
+        MockTransport::input_frame([0, 0, b'S', 0b0000_0001, 0, 0, 0], [0; 100]),
+    ]);
+    pixtend.set_short_read_retries(2);
+    pixtend.read_write_retry(3).unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 3);
+
+    assert!(matches!(
+        pixtend.read_write_retry(2),
+        Err(PiXtendError::InvalidSpiResponseLength {
+            expected: 111,
+            actual: 50
+        })
+    ));
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 5);
+
+    assert!(matches!(
+        pixtend.read_write_retry(3),
+        Err(PiXtendError::PiXtendModelMismatch)
+    ));
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 6);
+
+    // The short read retries are restored for plain `read_write` calls
+    pixtend.spi_pixtend.lock().unwrap().responses.extend([
+        vec![0; 50],
+        vec![0; 50],
+        MockTransport::input_frame(header, [0; 100]),
+    ]);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 9);
+}
+
+#[test]
+fn test_gpio_pullup_active() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_gpio_pullup_enable(true);
+    assert_eq!(pixtend.gpio_pullup_active(0).unwrap(), false);
+    pixtend.set_gpio_config(0, GpioConfig::Input(true)).unwrap();
+    pixtend
+        .set_gpio_config(1, GpioConfig::Input(false))
+        .unwrap();
+    assert_eq!(pixtend.gpio_pullup_active(0).unwrap(), true);
+    assert_eq!(pixtend.gpio_pullup_active(1).unwrap(), false);
+
+    pixtend.set_gpio_pullup_enable(false);
+    assert_eq!(pixtend.gpio_pullup_active(0).unwrap(), false);
+    assert!(matches!(
+        pixtend.gpio_pullup_active(4),
+        Err(PiXtendError::InvalidGpioInputIndex(4))
+    ));
+}
+
+#[test]
+fn test_system_settings_readback() {
+    let mut pixtend = PiXtend::mocked([]);
+    assert_eq!(pixtend.get_watchdog(), Watchdog::default());
+    assert_eq!(pixtend.get_retain_enable(), false);
+    assert_eq!(pixtend.get_led_disable(), false);
+    assert_eq!(pixtend.get_gpio_pullup_enable(), false);
+
+    pixtend.set_watchdog(Watchdog::Activated1s);
+    pixtend.set_retain_enable(true);
+    pixtend.set_led_disable(true);
+    pixtend.set_gpio_pullup_enable(true);
+    assert_eq!(pixtend.get_watchdog(), Watchdog::Activated1s);
+    assert_eq!(pixtend.get_retain_enable(), true);
+    assert_eq!(pixtend.get_led_disable(), true);
+    assert_eq!(pixtend.get_gpio_pullup_enable(), true);
+}
+
+#[test]
+fn test_last_cycle_duration() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, [0; 100]),
+        MockTransport::input_frame(header, [0; 100]),
+    ]);
+    pixtend.set_cycle_delay(Duration::from_millis(5));
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.last_cycle_duration(), None);
+    pixtend.read_write().unwrap();
+    assert!(pixtend.last_cycle_duration().unwrap() >= Duration::from_millis(5));
+}
+
+#[test]
+fn test_dac_disabled() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, [0; 100]),
+        MockTransport::input_frame(header, [0; 100]),
+    ]);
+    pixtend.set_dac_enabled(false);
+    pixtend.set_analog_output(Channel::A, Some(5.0));
+    pixtend.read_write().unwrap();
+    assert!(pixtend.spi_dac.lock().unwrap().written.is_empty());
+
+    pixtend.set_dac_enabled(true);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.spi_dac.lock().unwrap().written.len(), 2);
+}
+
+#[test]
+fn test_status_summary() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([5, 21, b'L', 0b0000_0001, 0b0000_1010, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.status_summary(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    assert_eq!(
+        pixtend.status_summary().unwrap(),
+        "PiXtend L firmware 5 hardware 21, running, warnings: I2C error, retain CRC error, \
+         error code: NoError"
+    );
+}
+
+#[test]
+fn test_debounce_ms() {
+    let mut pixtend = PiXtend::mocked([]);
+    assert!(matches!(
+        pixtend.set_digital_debounce_ms(0, 90),
+        Err(PiXtendError::DebounceTimeOutOfRange(90))
+    ));
+
+    pixtend.set_cycle_delay(COMMUNICATION_DELAY);
+    pixtend.set_digital_debounce_ms(2, 95).unwrap();
+    pixtend.set_gpio_debounce_ms(1, 7650).unwrap();
+    assert_eq!(pixtend.get_config().digital_debounce[2], 3);
+    assert_eq!(pixtend.get_config().gpio_debounce[1], 255);
+
+    assert!(matches!(
+        pixtend.set_digital_debounce_ms(0, 7680),
+        Err(PiXtendError::DebounceTimeOutOfRange(7680))
+    ));
+    assert!(matches!(
+        pixtend.set_gpio_debounce_ms(2, 30),
+        Err(PiXtendError::InvalidGpioDebounceGroup(2))
+    ));
+
+    // The conversion follows a custom cycle delay
+    pixtend.set_cycle_delay(Duration::from_millis(10));
+    pixtend.set_digital_debounce_ms(2, 95).unwrap();
+    assert_eq!(pixtend.get_config().digital_debounce[2], 9);
+}
+
+#[test]
+fn test_cycle() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::mocked([frame]);
+    pixtend
+        .cycle(|pixtend| pixtend.set_relay_output(0, true).unwrap())
+        .unwrap();
+    assert_eq!(
+        pixtend.spi_pixtend.lock().unwrap().written[0],
+        pixtend.last_output_bytes().unwrap()
+    );
+    assert_eq!(pixtend.get_relay_output(0).unwrap(), true);
+    assert_eq!(pixtend.is_running().unwrap(), true);
+}
+
+#[test]
+fn test_sensor_smoothing() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let frame = |temperature: u16| {
+        let mut data = [0u8; 100];
+        data[15..17].copy_from_slice(&temperature.to_le_bytes());
+        data[17..19].copy_from_slice(&500u16.to_le_bytes());
+        MockTransport::input_frame(header, data)
+    };
+    let mut pixtend = PiXtend::mocked([frame(215), frame(900), frame(225), frame(215)]);
+    pixtend.set_gpio_config(0, GpioConfig::Sensor).unwrap();
+    pixtend.set_sensor_smoothing(0, 3).unwrap();
+    assert!(pixtend.set_sensor_smoothing(4, 3).is_err());
+
+    pixtend.read_write().unwrap();
+    let temperature = pixtend.get_gpio_temperature(0, SensorKind::DHT22);
+    assert_eq!(temperature.unwrap(), 21.5);
+    pixtend.read_write().unwrap();
+    let temperature = pixtend.get_gpio_temperature(0, SensorKind::DHT22);
+    assert_eq!(temperature.unwrap(), 21.5);
+    pixtend.read_write().unwrap();
+    let temperature = pixtend.get_gpio_temperature(0, SensorKind::DHT22);
+    assert_eq!(temperature.unwrap(), 22.0);
+    pixtend.read_write().unwrap();
+    let temperature = pixtend.get_gpio_temperature(0, SensorKind::DHT22);
+    assert_eq!(temperature.unwrap(), 22.0);
+    assert_eq!(
+        pixtend.get_gpio_humidity(0, SensorKind::DHT22).unwrap(),
+        0.5
+    );
+
+    pixtend.set_sensor_smoothing(0, 0).unwrap();
+    assert!(matches!(
+        pixtend.get_gpio_temperature(0, SensorKind::DHT22),
+        Ok(temperature) if temperature == 21.5
+    ));
+}
+
+#[test]
+fn test_output_debug() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_watchdog(Watchdog::Activated1s);
+    pixtend.set_digital_debounce(7, 4).unwrap();
+    pixtend.set_digital_outputs_mask(0b1000_0000_0001).unwrap();
+    pixtend.set_relays_mask(0b0110).unwrap();
+    pixtend.set_gpio_config(1, GpioConfig::Output).unwrap();
+    pixtend.set_gpio_output(1, true).unwrap();
+    let config = PwmConfig::for_frequency(1.0, PwmMode::DutyCycle).unwrap();
+    pixtend.set_pwm_config(2, config).unwrap();
+    pixtend.set_pwm_duty_cycle(2, Channel::B, 100).unwrap();
+    pixtend.set_analog_output_raw(Channel::B, 512);
+
+    let debug = pixtend.output_debug();
+    assert_eq!(debug.watchdog, Watchdog::Activated1s);
+    assert_eq!(debug.digital_debounce, [0, 0, 0, 0, 0, 0, 0, 4]);
+    assert_eq!(debug.digital_outputs, 0b1000_0000_0001);
+    assert_eq!(debug.relay_outputs, 0b0110);
+    assert_eq!(debug.gpio_io, [false, true, false, false]);
+    assert_eq!(debug.gpio_outputs, [false, true, false, false]);
+    assert_eq!(
+        debug.pwm[2],
+        PwmGroupDebug {
+            mode: PwmMode::DutyCycle,
+            prescaler: PwmPrescaler::Prescale62_5kHz,
+            channel_a_enabled: true,
+            channel_b_enabled: true,
+            frequency: 31250,
+            channel_a: 0,
+            channel_b: 100,
+        }
+    );
+    assert_eq!(
+        debug.dac,
+        [
+            DacDebug {
+                enabled: false,
+                value: 0
+            },
+            DacDebug {
+                enabled: true,
+                value: 512
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_analog_current_percent() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut data = [0u8; 100];
+    data[10..12].copy_from_slice(&595u16.to_le_bytes());
+    data[12..14].copy_from_slice(&100u16.to_le_bytes());
+    let mut pixtend = PiXtend::mocked([MockTransport::input_frame(header, data)]);
+    pixtend.read_write().unwrap();
+
+    let percent = pixtend.get_analog_current_percent(4).unwrap();
+    assert!((percent - (595.0 * input::CURRENT_SCALE - 4.0) / 16.0).abs() < 1e-9);
+    assert!(matches!(
+        pixtend.get_analog_current_percent(5),
+        Err(PiXtendError::CurrentLoopBroken { index: 5, .. })
+    ));
+    assert!(matches!(
+        pixtend.get_analog_current_percent(3),
+        Err(PiXtendError::InvalidAnalogCurrentInputIndex(3))
+    ));
+
+    // The calibration of the current input is applied
+    pixtend.set_current_calibration(4, 0.0, 12.0).unwrap();
+    assert_eq!(pixtend.get_analog_current_percent(4).unwrap(), 0.5);
+}
+
+#[test]
+fn test_servo_calibration() {
+    let mut pixtend = PiXtend::mocked([]);
+    let servo = PwmConfig::Servo {
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(1, servo).unwrap();
+    pixtend.set_servo_calibration(1, 1100, 1900, 270.0).unwrap();
+    pixtend.set_pwm_servo_angle(1, Channel::A, 0.0).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group1.channel0, 1600);
+    pixtend.set_pwm_servo_angle(1, Channel::A, 135.0).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group1.channel0, 8000);
+    pixtend.set_pwm_servo_angle(1, Channel::B, 300.0).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group1.channel1, 14400);
+
+    assert!(matches!(
+        pixtend.set_servo_calibration(1, 900, 1900, 270.0),
+        Err(PiXtendError::InvalidServoCalibration(1))
+    ));
+    assert!(matches!(
+        pixtend.set_servo_calibration(1, 1500, 1500, 270.0),
+        Err(PiXtendError::InvalidServoCalibration(1))
+    ));
+    assert!(pixtend.set_servo_calibration(1, 1000, 2000, 0.0).is_err());
+    assert!(matches!(
+        pixtend.set_servo_calibration(3, 900, 2000, 180.0),
+        Err(PiXtendError::InvalidPwmOutputGroupIndex(3))
+    ));
+}
+
+#[test]
+fn test_flush() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_cycle_delay(Duration::from_secs(60));
+    pixtend.set_relay_output(0, true).unwrap();
+    pixtend.flush().unwrap();
+    pixtend.clear_outputs();
+    pixtend.flush().unwrap();
+
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 2);
+    assert_eq!(
+        pixtend.spi_pixtend.lock().unwrap().written[1],
+        pixtend.last_output_bytes().unwrap()
+    );
+    assert_eq!(pixtend.spi_dac.lock().unwrap().written.len(), 4);
+    assert_eq!(pixtend.try_read_write().unwrap(), false);
+}
+
+#[test]
+fn test_gpio_output_on_sensor() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_gpio_config(2, GpioConfig::Sensor).unwrap();
+    assert!(matches!(
+        pixtend.set_gpio_output(2, true),
+        Err(PiXtendError::GpioConfiguredAsSensor(2))
+    ));
+    assert!(matches!(
+        pixtend.get_gpio_output(2),
+        Err(PiXtendError::GpioConfiguredAsSensor(2))
+    ));
+    assert!(matches!(
+        pixtend.set_gpio_output(1, true),
+        Err(PiXtendError::GpioNotConfiguredAsOutput(1))
+    ));
+}
+
+#[test]
+fn test_get_analog_output() {
+    let mut pixtend = PiXtend::mocked([]);
+    assert_eq!(pixtend.get_analog_output(Channel::A), None);
+
+    pixtend.set_analog_output(Channel::A, Some(10.0));
+    pixtend.set_analog_output_raw(Channel::B, 0);
+    assert_eq!(pixtend.get_analog_output(Channel::A), Some(10.0));
+    assert_eq!(pixtend.get_analog_output(Channel::B), Some(0.0));
+
+    pixtend.set_analog_output(Channel::A, Some(5.0));
+    let voltage = pixtend.get_analog_output(Channel::A).unwrap();
+    assert!((voltage - 5.0).abs() < 10.0 / 1023.0);
+
+    pixtend.set_analog_output(Channel::A, None);
+    assert_eq!(pixtend.get_analog_output(Channel::A), None);
+}
+
+#[test]
+fn test_retain_copy_check() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([MockTransport::input_frame(header, [0; 100])]);
+    pixtend.set_retain_copy(true);
+    assert!(matches!(
+        pixtend.validate_config(),
+        Err(PiXtendError::RetainCopyWithoutEnable)
+    ));
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::RetainCopyWithoutEnable)
+    ));
+
+    pixtend.set_retain_enable(true);
+    pixtend.validate_config().unwrap();
+
+    pixtend.set_retain_enable(false);
+    pixtend.set_retain_copy_check(false);
+    pixtend.read_write().unwrap();
+}
+
+#[test]
+fn test_analog_input_saturated() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0u8; 100];
+    data[2..4].copy_from_slice(&1023u16.to_le_bytes());
+    data[4..6].copy_from_slice(&512u16.to_le_bytes());
+    data[6..8].copy_from_slice(&1u16.to_le_bytes());
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.analog_input_saturated(0),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.analog_input_saturated(0).unwrap(), true);
+    assert_eq!(pixtend.analog_input_saturated(1).unwrap(), false);
+    assert_eq!(pixtend.analog_input_saturated(2).unwrap(), false);
+    assert_eq!(pixtend.analog_input_saturated(3).unwrap(), true);
+    assert!(pixtend.analog_input_saturated(6).is_err());
+}
+
+#[test]
+fn test_set_pwm_group_values() {
+    let mut pixtend = PiXtend::mocked([]);
+    assert!(matches!(
+        pixtend.set_pwm_group_values(0, 1, 2),
+        Err(PiXtendError::PwmGroupDeactivated(0))
+    ));
+    assert!(matches!(
+        pixtend.set_pwm_group_values(3, 1, 2),
+        Err(PiXtendError::InvalidPwmOutputGroupIndex(3))
+    ));
+
+    let servo = PwmConfig::Servo {
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(0, servo).unwrap();
+    pixtend.set_pwm_group_values(0, 4000, 12000).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 4000);
+    assert_eq!(pixtend.output.data.pwm.group0.channel1, 12000);
+
+    // An invalid channel B must not stage channel A either
+    assert!(matches!(
+        pixtend.set_pwm_group_values(0, 8000, 16001),
+        Err(PiXtendError::PwmServoValueOutOfRange {
+            index: 0,
+            value: 16001
+        })
+    ));
+    assert_eq!(pixtend.output.data.pwm.group0.channel0, 4000);
+
+    let universal = PwmConfig::for_frequency(1.0, PwmMode::Universal).unwrap();
+    pixtend.set_pwm_config(1, universal).unwrap();
+    assert!(matches!(
+        pixtend.set_pwm_group_values(1, 1, 2),
+        Err(PiXtendError::PwmChannelNotSettable {
+            index: 1,
+            channel: Channel::B
+        })
+    ));
+    assert_eq!(pixtend.output.data.pwm.group1.channel0, 0);
+    pixtend.set_pwm_group_values(1, 15625, 0).unwrap();
+    assert_eq!(pixtend.output.data.pwm.group1.channel0, 15625);
+    assert_eq!(pixtend.output.data.pwm.group1.channel1, 0);
+
+    let frequency = PwmConfig::for_frequency(1000.0, PwmMode::Frequency).unwrap();
+    pixtend.set_pwm_config(2, frequency).unwrap();
+    assert!(pixtend.set_pwm_group_values(2, 1000, 1).is_err());
+}
+
+#[test]
+fn test_output_crcs() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_digital_output(0, true).unwrap();
+    let (header_crc, data_crc) = pixtend.output_crcs().unwrap();
+    assert!(pixtend.spi_pixtend.lock().unwrap().written.is_empty());
+
+    let frame = pixtend.output.to_bytes().unwrap();
+    assert_eq!(header_crc, crate::crc16(&frame[..7]));
+    assert_eq!(data_crc, crate::crc16(&frame[9..109]));
+    assert_eq!(frame[7..9], header_crc.to_le_bytes());
+    assert_eq!(frame[109..], data_crc.to_le_bytes());
+}
+
+#[test]
+fn test_short_read_retries() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([
+        vec![0; 50],
+        MockTransport::input_frame(header, [0; 100]),
+        vec![0; 50],
+        vec![0; 50],
+        vec![0; 50],
+    ]);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 2);
+    assert_eq!(pixtend.stats().short_frames, 1);
+
+    pixtend.set_short_read_retries(2);
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::InvalidSpiResponseLength {
+            expected: 111,
+            actual: 50
+        })
+    ));
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 5);
+}
+
+#[test]
+fn test_reference_voltage() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0u8; 100];
+    data[2..4].copy_from_slice(&512u16.to_le_bytes());
+    data[4..6].copy_from_slice(&512u16.to_le_bytes());
+    let frame = MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    pixtend
+        .set_reference_voltage(1, ReferenceVoltage::V5)
+        .unwrap();
+    assert!(matches!(
+        pixtend.set_reference_voltage(4, ReferenceVoltage::V5),
+        Err(PiXtendError::InvalidAnalogVoltageInputIndex(4))
+    ));
+    pixtend.read_write().unwrap();
+
+    assert_eq!(pixtend.get_analog_voltage_input(0).unwrap(), 5.0);
+    assert_eq!(pixtend.get_analog_voltage_input(1).unwrap(), 2.5);
+    assert_eq!(
+        pixtend
+            .get_analog_voltage_input_with_reference(1, ReferenceVoltage::V10)
+            .unwrap(),
+        5.0
+    );
+    assert!(pixtend.get_analog_voltage_input(4).is_err());
+}
+
+#[test]
+fn test_skip_unchanged() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, [0; 100]),
+        MockTransport::input_frame(header, [0; 100]),
+        MockTransport::input_frame(header, [0; 100]),
+    ]);
+    pixtend.read_write().unwrap();
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 2);
+
+    pixtend.set_skip_unchanged(true);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 3);
+    assert_eq!(pixtend.last_cycle_skipped(), false);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.last_cycle_skipped(), true);
+    assert_eq!(pixtend.try_read_write().unwrap(), false);
+    pixtend.write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 3);
+
+    pixtend.set_digital_output(0, true).unwrap();
+    pixtend.write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 4);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 4);
+
+    pixtend.set_analog_output(Channel::A, Some(5.0));
+    pixtend.write().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 5);
+
+    pixtend.flush().unwrap();
+    assert_eq!(pixtend.spi_pixtend.lock().unwrap().written.len(), 6);
+    assert_eq!(pixtend.last_cycle_skipped(), false);
+
+    // Skipped cycles are still spaced by the cycle delay
+    pixtend.set_cycle_delay(Duration::from_millis(20));
+    let start = Instant::now();
+    pixtend.read_write().unwrap();
+    pixtend.read_write().unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(20));
+    assert_eq!(pixtend.last_cycle_skipped(), true);
+
+    // A board that left the run state is reported before the transfer is skipped
+    pixtend.set_cycle_delay(Duration::ZERO);
+    let stopped = MockTransport::input_frame([0, 0, b'L', 0, 0, 0, 0], [0; 100]);
+    pixtend
+        .spi_pixtend
+        .lock()
+        .unwrap()
+        .responses
+        .push_back(stopped);
+    pixtend.set_digital_output(1, true).unwrap();
+    pixtend.read_write().unwrap();
+    assert!(matches!(
+        pixtend.write(),
+        Err(PiXtendError::NotReadyForCommunication)
+    ));
+}
+
+#[test]
+fn test_warning_latches() {
+    use crate::transport::MockTransport;
+
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0b0000_1000, 0, 0], [0; 100]),
+        MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], [0; 100]),
+    ]);
+    pixtend.read_write().unwrap();
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.get_warnings().unwrap().i2c_error, false);
+    assert_eq!(pixtend.i2c_error_latched(), true);
+    assert_eq!(pixtend.voltage_error_latched(), false);
+    assert_eq!(pixtend.retain_crc_error_latched(), false);
+
+    pixtend.clear_warning_latches();
+    assert_eq!(pixtend.i2c_error_latched(), false);
+}
+
+#[test]
+fn test_require_firmware() {
+    use crate::transport::MockTransport;
+
+    let frame = MockTransport::input_frame([5, 21, b'L', 0b0000_0001, 0, 0, 0], [0; 100]);
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.require_firmware(1),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    pixtend.require_firmware(4).unwrap();
+    pixtend.require_firmware(5).unwrap();
+    assert!(matches!(
+        pixtend.require_firmware(6),
+        Err(PiXtendError::FirmwareTooOld {
+            found: 5,
+            required: 6
+        })
+    ));
+}
+
+#[test]
+fn test_set_gpio_configs() {
+    let mut pixtend = PiXtend::mocked([]);
+    let configs = [
+        GpioConfig::Output,
+        GpioConfig::Input(true),
+        GpioConfig::Input(false),
+        GpioConfig::Sensor,
+    ];
+    assert!(matches!(
+        pixtend.set_gpio_configs(configs),
+        Err(PiXtendError::GpioPullupNotGloballyEnabled)
+    ));
+    assert_eq!(pixtend.gpio_configs, [GpioConfig::default(); 4]);
+
+    pixtend.set_gpio_pullup_enable(true);
+    pixtend.set_gpio_configs(configs).unwrap();
+    assert_eq!(pixtend.gpio_configs, configs);
+
+    // A sensor conflicts with an active PWM group, so nothing is applied
+    pixtend.set_gpio_configs([GpioConfig::Output; 4]).unwrap();
+    let servo = PwmConfig::Servo {
+        channel_a: true,
+        channel_b: false,
+    };
+    pixtend.set_pwm_config(0, servo).unwrap();
+    assert!(matches!(
+        pixtend.set_gpio_configs(configs),
+        Err(PiXtendError::PwmAndDhtExclusive)
+    ));
+    assert_eq!(pixtend.gpio_configs, [GpioConfig::Output; 4]);
+}
+
+#[test]
+fn test_retain_array() {
+    use crate::transport::MockTransport;
+
+    let mut data = [0; 100];
+    data[36] = 0xAB;
+    data[99] = 0xCD;
+    let frame = MockTransport::input_frame([0, 21, b'L', 0b0000_0001, 0, 0, 0], data);
+    let mut pixtend = PiXtend::mocked([frame]);
+    assert!(matches!(
+        pixtend.retain_slice(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+
+    pixtend.read_write().unwrap();
+    let retain = pixtend.get_retain_array().unwrap();
+    assert_eq!(retain[0], 0xAB);
+    assert_eq!(retain[RETAIN_SIZE - 1], 0xCD);
+    assert_eq!(pixtend.retain_slice().unwrap(), retain);
+    assert_eq!(pixtend.get_retain_data().unwrap(), retain);
+}
+
+#[test]
+fn test_pwm_prescaler_deactivated() {
+    let mut pixtend = PiXtend::mocked([]);
+    let servo = PwmConfig::Servo {
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(1, servo).unwrap();
+    pixtend.set_pwm_servo(1, Channel::A, 8000).unwrap();
+
+    // Switching from servo to frequency without choosing a prescaler
+    let frequency = PwmConfig::Frequency {
+        prescaler: PwmPrescaler::Deactivated,
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(1, frequency).unwrap();
+    assert!(matches!(
+        pixtend.set_pwm_frequency(1, Channel::A, 100),
+        Err(PiXtendError::PwmPrescalerDeactivated(1))
+    ));
+    assert!(matches!(
+        pixtend.set_pwm_group_values(1, 100, 200),
+        Err(PiXtendError::PwmPrescalerDeactivated(1))
+    ));
+
+    let duty_cycle = PwmConfig::DutyCycle {
+        prescaler: PwmPrescaler::Deactivated,
+        frequency: 1000,
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(1, duty_cycle).unwrap();
+    assert!(matches!(
+        pixtend.set_pwm_duty_cycle(1, Channel::A, 500),
+        Err(PiXtendError::PwmPrescalerDeactivated(1))
+    ));
+}
+
+#[test]
+fn test_input_events() {
+    use crate::transport::MockTransport;
+
+    let mut first = [0u8; 100];
+    first[0] = 0b0000_0011;
+    let mut second = [0u8; 100];
+    second[0] = 0b0000_0110;
+    second[1] = 0b1000_0000;
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut pixtend = PiXtend::mocked([
+        MockTransport::input_frame(header, first),
+        MockTransport::input_frame(header, second),
+    ]);
+    assert!(pixtend.input_events().is_err());
+
+    pixtend.read_write().unwrap();
+    assert!(pixtend.input_events().unwrap().is_empty());
+
+    let before = Instant::now();
+    pixtend.read_write().unwrap();
+    let events = pixtend.input_events().unwrap();
+    let indexes: Vec<_> = events
+        .iter()
+        .map(|event| (event.index, event.rising))
+        .collect();
+    assert_eq!(indexes, [(0, false), (2, true), (15, true)]);
+    assert!(events.iter().all(|event| event.at >= before));
+}
+
+#[test]
+fn test_checked_index_setters() {
+    let mut pixtend = PiXtend::mocked([]);
+    let output = DigitalOutputIndex::new(11).unwrap();
+    pixtend.set_digital_output_at(output, true).unwrap();
+    assert_eq!(pixtend.get_digital_output(11).unwrap(), true);
+
+    let relay = RelayIndex::new(2).unwrap();
+    pixtend.set_relay_output_at(relay, true).unwrap();
+    assert_eq!(pixtend.get_relays_mask(), 0b0100);
+
+    let gpio = GpioIndex::new(3).unwrap();
+    pixtend
+        .set_gpio_config_at(gpio, GpioConfig::Output)
+        .unwrap();
+    assert_eq!(pixtend.gpio_configs[3], GpioConfig::Output);
+
+    let group = PwmGroupIndex::new(2).unwrap();
+    let servo = PwmConfig::Servo {
+        channel_a: true,
+        channel_b: false,
+    };
+    pixtend.set_pwm_config_at(group, servo).unwrap();
+    assert_eq!(pixtend.pwm_configs[2], servo);
+}
+
+#[test]
+fn test_stall_detection() {
+    use crate::transport::MockTransport;
+
+    let header = [0, 0, b'L', 0b0000_0001, 0, 0, 0];
+    let mut changed = [0u8; 100];
+    changed[0] = 0b0000_0001;
+    let frame = MockTransport::input_frame(header, [0; 100]);
+    let mut pixtend = PiXtend::mocked([
+        frame.clone(),
+        frame.clone(),
+        frame.clone(),
+        MockTransport::input_frame(header, changed),
+        frame,
+    ]);
+    assert!(matches!(
+        pixtend.set_stall_detection(Some(1)),
+        Err(PiXtendError::InvalidStallThreshold(1))
+    ));
+    pixtend.set_stall_detection(Some(3)).unwrap();
+
+    pixtend.read_write().unwrap();
+    pixtend.read_write().unwrap();
+    assert!(matches!(
+        pixtend.read_write(),
+        Err(PiXtendError::LinkStalled(3))
+    ));
+
+    // A changed frame restarts the count
+    pixtend.read_write().unwrap();
+    pixtend.read_write().unwrap();
+}
+
+#[test]
+fn test_soft_reset() {
+    use crate::transport::MockTransport;
+
+    let frame = |analog: u16, temperature: u16| {
+        let mut data = [0u8; 100];
+        data[2..4].copy_from_slice(&analog.to_le_bytes());
+        data[15..17].copy_from_slice(&temperature.to_le_bytes());
+        data[17..19].copy_from_slice(&500u16.to_le_bytes());
+        MockTransport::input_frame([0, 0, b'L', 0b0000_0001, 0, 0, 0], data)
+    };
+    let mut pixtend = PiXtend::mocked([
+        frame(512, 200),
+        frame(512, 200),
+        frame(256, 210),
+        frame(256, 700),
+    ]);
+    pixtend.set_analog_filter(0, 0.5).unwrap();
+    pixtend.set_sensor_smoothing(0, 3).unwrap();
+    pixtend.set_gpio_pullup_enable(true);
+    pixtend.set_gpio_config(0, GpioConfig::Output).unwrap();
+    pixtend.set_gpio_config(1, GpioConfig::Input(true)).unwrap();
+    pixtend.set_gpio_output(0, true).unwrap();
+    let config = PwmConfig::Servo {
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(0, config).unwrap();
+    pixtend.set_pwm_servo(0, Channel::A, 8000).unwrap();
+    pixtend.set_digital_output(2, true).unwrap();
+    pixtend.set_relay_output(1, true).unwrap();
+    pixtend.read_write().unwrap();
+
+    pixtend.soft_reset();
+    assert!(matches!(
+        pixtend.get_digital_inputs(),
+        Err(PiXtendError::NoInputDataAvailable)
+    ));
+    assert_eq!(pixtend.get_digital_output(2).unwrap(), false);
+    assert_eq!(pixtend.get_relays_mask(), 0);
+    assert_eq!(
+        pixtend
+            .output
+            .data
+            .pwm
+            .get_channel_value(0, Channel::A)
+            .unwrap(),
+        0
+    );
+    assert_eq!(pixtend.output.data.gpio_out.gpio0, false);
+
+    // The configuration and the pullup of the input are kept
+    assert_eq!(pixtend.gpio_configs[0], GpioConfig::Output);
+    assert_eq!(pixtend.pwm_configs[0], config);
+    assert_eq!(pixtend.output.header.system.gpio_pullup_enable, true);
+    assert_eq!(pixtend.output.data.gpio_out.gpio1, true);
+    assert_eq!(pixtend.output.data.pwm.group0.ctrl0.mode, PwmMode::Servo);
+
+    // The filter weight and the smoothing size still apply, only their history is cleared
+    pixtend.set_pwm_config(0, PwmConfig::Deactivated).unwrap();
+    pixtend.set_gpio_config(0, GpioConfig::Sensor).unwrap();
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.get_analog_voltage_input_filtered(0).unwrap(), 5.0);
+    pixtend.read_write().unwrap();
+    assert_eq!(pixtend.get_analog_voltage_input_filtered(0).unwrap(), 3.75);
+    pixtend.read_write().unwrap();
+    let temperature = pixtend.get_gpio_temperature(0, SensorKind::DHT22);
+    assert_eq!(temperature.unwrap(), 21.0);
+}
+
+#[test]
+fn test_set_retain_slice() {
+    let mut pixtend = PiXtend::mocked([]);
+    let data = [0x12; 8];
+    assert!(matches!(
+        pixtend.set_retain_slice(&data),
+        Err(PiXtendError::RetainDataNotGloballyEnabled)
+    ));
+
+    pixtend.set_retain_enable(true);
+    pixtend.set_retain_slice(&data).unwrap();
+    assert_eq!(pixtend.output.data.retain.storage[..8], data);
+    assert_eq!(
+        pixtend.output.data.retain.storage[8..],
+        [0; RETAIN_SIZE - 8]
+    );
+}
+
+#[test]
+fn test_write_dac() {
+    let mut pixtend = PiXtend::mocked([]);
+    pixtend.set_dac_enabled(false);
+    pixtend.set_analog_output_raw(Channel::A, 0x155);
+    pixtend.set_analog_output(Channel::B, None);
+    pixtend.write_dac().unwrap();
+
+    // Only the DAC is written, the PiXtend frame is untouched
+    assert!(pixtend.spi_pixtend.lock().unwrap().written.is_empty());
+    assert_eq!(
+        pixtend.spi_dac.lock().unwrap().written,
+        [
+            Dac::from_raw(Channel::A, 0x155).to_bytes().unwrap(),
+            Dac::disabled(Channel::B).to_bytes().unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn test_record_to() {
+    use crate::transport::MockTransport;
+
+    let path = std::env::temp_dir().join(format!("pixtend-session-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let header = [0, 21, b'L', 0b0000_0001, 0, 0, 0];
+    let mut data = [0u8; 100];
+    data[0] = 0b0000_0101;
+    let first = MockTransport::input_frame(header, data);
+    let second = MockTransport::input_frame(header, [0; 100]);
+    let mut pixtend = PiXtend::mocked([first.clone(), second.clone(), first.clone()]);
+    pixtend.record_to(&path).unwrap();
+    pixtend.read_write().unwrap();
+    pixtend.set_recording(false);
+    pixtend.read_write().unwrap();
+    pixtend.set_recording(true);
+    pixtend.read_write().unwrap();
+    pixtend.write().unwrap();
+    pixtend.stop_recording().unwrap();
+
+    let records: Vec<_> = crate::replay(&path)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[2].0, pixtend.spi_pixtend.lock().unwrap().written[3]);
+    assert!(records[2].1.is_empty());
+    assert_eq!(records[0].0, pixtend.spi_pixtend.lock().unwrap().written[0]);
+    assert_eq!(records[1].1, first);
+
+    // The recorded responses can be parsed again offline
+    let (_, input) = Input::from_bytes((records[0].1.as_ref(), 0)).unwrap();
+    assert_eq!(input.data.digital_in.as_mask(), 0b0000_0101);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_set_pwm_frequency_hz() {
+    let mut pixtend = PiXtend::mocked([]);
+    let config = PwmConfig::Frequency {
+        prescaler: PwmPrescaler::Prescale62_5kHz,
+        channel_a: true,
+        channel_b: true,
+    };
+    pixtend.set_pwm_config(0, config).unwrap();
+
+    pixtend.set_pwm_frequency_hz(0, Channel::A, 1.0).unwrap();
+    assert_eq!(
+        pixtend
+            .output
+            .data
+            .pwm
+            .get_channel_value(0, Channel::A)
+            .unwrap(),
+        31250
+    );
+
+    // 31.25 is rounded to 31, which results in about 1008Hz
+    pixtend.set_pwm_frequency_hz(0, Channel::B, 1000.0).unwrap();
+    assert_eq!(
+        pixtend
+            .output
+            .data
+            .pwm
+            .get_channel_value(0, Channel::B)
+            .unwrap(),
+        31
+    );
+    let hz = pixtend.get_pwm_frequency_hz(0, Channel::B).unwrap();
+    assert!((hz - 1008.06).abs() < 0.01);
+
+    // Too low for the 16 bit divider, too high for the prescaler and too high for the hardware
+    assert!(matches!(
+        pixtend.set_pwm_frequency_hz(0, Channel::A, 0.1),
+        Err(PiXtendError::PwmFrequencyUnreachable { index: 0, .. })
+    ));
+    assert!(matches!(
+        pixtend.set_pwm_frequency_hz(0, Channel::A, 100_000.0),
+        Err(PiXtendError::PwmFrequencyUnreachable { index: 0, .. })
+    ));
+    assert!(matches!(
+        pixtend.set_pwm_frequency_hz(0, Channel::A, 25_000.0),
+        Err(PiXtendError::PwmFrequencyOutOfRange { index: 0, .. })
+    ));
+    assert!(matches!(
+        pixtend.set_pwm_frequency_hz(1, Channel::A, 1.0),
+        Err(PiXtendError::PwmNotConfiguredAsFrequency(1))
+    ));
+}
@@ -0,0 +1,177 @@
+use crate::{error::PiXtendError, InputSnapshot, PiXtend, SpiTransport};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+type OutputChange<T> = Box<dyn FnOnce(&mut PiXtend<T>) + Send>;
+
+/// Handle of a PiXtend instance that is cycled in a background thread, see
+/// `PiXtend::spawn_poller`. Dropping the handle stops the thread, which also drops the instance.
+pub struct PollerHandle<T: SpiTransport> {
+    latest: Arc<Mutex<Option<InputSnapshot>>>,
+    changes: Sender<OutputChange<T>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<PiXtend<T>, PiXtendError>>>,
+}
+
+impl<T: SpiTransport + Send + 'static> PiXtend<T> {
+    /// Moves the instance into a background thread, which calls `read_write` with the given
+    /// period and publishes the inputs of every successful cycle as a snapshot. The effective
+    /// period is never shorter than the cycle delay, see `set_cycle_delay`. Recoverable errors
+    /// are skipped, any other error stops the thread and is returned by `PollerHandle::stop`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use pixtend::PiXtend;
+    /// # use std::time::Duration;
+    /// let pixtend = PiXtend::new().unwrap();
+    /// let poller = pixtend.spawn_poller(Duration::from_millis(50));
+    ///
+    /// poller
+    ///     .stage(|pixtend| pixtend.set_relays_mask(0b0011).unwrap())
+    ///     .unwrap();
+    /// if let Some(snapshot) = poller.latest() {
+    ///     println!("Digital inputs: {:016b}", snapshot.digital_inputs);
+    /// }
+    ///
+    /// let pixtend = poller.stop().unwrap();
+    /// ```
+    pub fn spawn_poller(mut self, period: Duration) -> PollerHandle<T> {
+        let latest = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (changes, receiver) = mpsc::channel::<OutputChange<T>>();
+
+        let thread = {
+            let latest = Arc::clone(&latest);
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let next = Instant::now() + period;
+                    self.poll(&receiver, &latest)?;
+                    thread::sleep(next.saturating_duration_since(Instant::now()));
+                }
+                Ok(self)
+            })
+        };
+
+        PollerHandle {
+            latest,
+            changes,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Applies the staged output changes, performs one cycle and publishes the inputs
+    fn poll(
+        &mut self,
+        receiver: &Receiver<OutputChange<T>>,
+        latest: &Mutex<Option<InputSnapshot>>,
+    ) -> Result<(), PiXtendError> {
+        for change in receiver.try_iter() {
+            change(self);
+        }
+
+        match self.read_write() {
+            Ok(()) => {}
+            Err(error) if error.is_recoverable() => return Ok(()),
+            Err(error) => return Err(error),
+        }
+
+        let snapshot = self.snapshot()?;
+        *latest.lock().unwrap_or_else(|e| e.into_inner()) = Some(snapshot);
+
+        Ok(())
+    }
+}
+
+impl<T: SpiTransport> PollerHandle<T> {
+    /// Returns the inputs of the last successful cycle, or `None` if no cycle has been completed
+    /// yet.
+    pub fn latest(&self) -> Option<InputSnapshot> {
+        *self.latest.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Stages an output change, which is applied to the instance right before the next cycle.
+    /// Changes are applied in the order they were staged.
+    /// Returns an error if the background thread has already stopped.
+    pub fn stage<F>(&self, change: F) -> Result<(), PiXtendError>
+    where
+        F: FnOnce(&mut PiXtend<T>) + Send + 'static,
+    {
+        self.changes
+            .send(Box::new(change))
+            .map_err(|_| PiXtendError::PollerStopped)
+    }
+
+    /// Returns whether the background thread is still cycling.
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Stops the background thread after the current cycle and returns the instance.
+    /// Returns the error that stopped the thread, if it has already stopped by itself.
+    pub fn stop(mut self) -> Result<PiXtend<T>, PiXtendError> {
+        self.join().unwrap_or(Err(PiXtendError::PollerStopped))
+    }
+
+    /// Signals the background thread to stop and waits for it to finish
+    fn join(&mut self) -> Option<Result<PiXtend<T>, PiXtendError>> {
+        self.stop.store(true, Ordering::Relaxed);
+        let thread = self.thread.take()?;
+        match thread.join() {
+            Ok(result) => Some(result),
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+}
+
+impl<T: SpiTransport> Drop for PollerHandle<T> {
+    fn drop(&mut self) {
+        // Don't propagate a panic of the thread while unwinding
+        if !thread::panicking() {
+            self.join();
+        }
+    }
+}
+
+#[test]
+fn test_poller() {
+    use crate::{ReferenceVoltage, SimulationTransport};
+
+    let simulation = SimulationTransport::default();
+    simulation.set_analog_input(1, || 512).unwrap();
+    let mut pixtend = PiXtend::new_with_transport(simulation, SimulationTransport::default());
+    pixtend.set_cycle_delay(Duration::ZERO);
+    pixtend
+        .set_reference_voltage(1, ReferenceVoltage::V5)
+        .unwrap();
+
+    let poller = pixtend.spawn_poller(Duration::from_millis(1));
+    poller
+        .stage(|pixtend| pixtend.set_digital_output(3, true).unwrap())
+        .unwrap();
+
+    // The simulation echoes the digital outputs onto the digital inputs
+    let started = Instant::now();
+    let snapshot = loop {
+        match poller.latest() {
+            Some(snapshot) if snapshot.digital_inputs & 0b1000 != 0 => break snapshot,
+            _ if started.elapsed() > Duration::from_secs(5) => panic!("poller didn't cycle"),
+            _ => thread::sleep(Duration::from_millis(1)),
+        }
+    };
+    assert!((snapshot.analog_voltages[1] - 2.5).abs() < 0.01);
+    assert!(poller.is_running());
+
+    let pixtend = poller.stop().unwrap();
+    assert_eq!(pixtend.get_digital_output(3).unwrap(), true);
+}
@@ -1,6 +1,12 @@
-use crate::output::PwmPrescaler;
+use crate::{
+    error::PiXtendError,
+    output::{PwmMode, PwmPrescaler},
+    utils::round,
+    Channel,
+};
 
 #[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PwmConfig {
     /// PWM is deactivated
     #[default]
@@ -52,3 +58,152 @@ pub enum PwmConfig {
         channel_b: bool,
     },
 }
+
+/// Highest output frequency supported by the PWM outputs
+pub(crate) const PWM_MAX_FREQUENCY_HZ: f64 = 20_000.0;
+
+/// Calculates the output frequency in Hz for the given prescaler and frequency value with
+/// `frequency = prescaler / 2 / value`. Returns `None` if the prescaler is deactivated.
+#[cfg(feature = "std")]
+pub(crate) fn frequency_hz(prescaler: PwmPrescaler, value: u16) -> Option<f64> {
+    prescaler
+        .base_hz()
+        .map(|base_hz| base_hz as f64 / 2.0 / value as f64)
+}
+
+impl PwmConfig {
+    /// Returns whether the value of the given channel can be set independently in this
+    /// configuration. A deactivated group has no settable channels and channel B of a Universal
+    /// group is derived from channel A with 50% duty cycle and half the frequency.
+    pub fn channel_settable(&self, channel: Channel) -> bool {
+        match self {
+            PwmConfig::Deactivated => false,
+            PwmConfig::Universal { .. } => channel == Channel::A,
+            PwmConfig::Servo { .. } | PwmConfig::DutyCycle { .. } | PwmConfig::Frequency { .. } => {
+                true
+            }
+        }
+    }
+
+    /// Creates a configuration for the given mode with the prescaler and frequency value that
+    /// get closest to the target frequency in Hz, with both channels enabled. The prescalers are
+    /// tried from the highest to the lowest base frequency, to get the best resolution for the
+    /// duty cycle. For `PwmMode::Frequency`, only the prescaler is selected, the frequency value
+    /// has to be set per channel via `set_pwm_frequency`. `PwmMode::Servo` always runs at 50Hz.
+    ///
+    /// Returns an error if no prescaler can represent the frequency, i.e. it is above the
+    /// maximum of `20kHz` or too low for the 16 bit frequency value.
+    ///
+    /// # Example
+    /// ```
+    /// # use pixtend::{PwmConfig, PwmMode, PwmPrescaler};
+    /// let config = PwmConfig::for_frequency(1.0, PwmMode::DutyCycle).unwrap();
+    /// assert_eq!(config, PwmConfig::DutyCycle {
+    ///     prescaler: PwmPrescaler::Prescale62_5kHz,
+    ///     frequency: 31250,
+    ///     channel_a: true,
+    ///     channel_b: true,
+    /// });
+    /// ```
+    pub fn for_frequency(target_hz: f64, mode: PwmMode) -> Result<PwmConfig, PiXtendError> {
+        let unreachable = PiXtendError::PwmFrequencyNotAvailable { hz: target_hz };
+        if !(target_hz > 0.0 && target_hz <= PWM_MAX_FREQUENCY_HZ) {
+            return Err(unreachable);
+        }
+
+        if mode == PwmMode::Servo {
+            return match target_hz == 50.0 {
+                true => Ok(PwmConfig::Servo {
+                    channel_a: true,
+                    channel_b: true,
+                }),
+                false => Err(unreachable),
+            };
+        }
+
+        // Find the prescaler and frequency value with the smallest frequency error
+        let mut best: Option<(PwmPrescaler, u16, f64)> = None;
+        for prescaler in PwmPrescaler::ACTIVE {
+            let base_hz = prescaler.base_hz().unwrap_or_default() as f64;
+            let value = round(base_hz / 2.0 / target_hz);
+            if !(1.0..=u16::MAX as f64).contains(&value) {
+                continue;
+            }
+
+            let error = (base_hz / 2.0 / value - target_hz).abs();
+            if best.is_none_or(|(_, _, best_error)| error < best_error) {
+                best = Some((prescaler, value as u16, error));
+            }
+        }
+
+        let (prescaler, frequency, _) = best.ok_or(unreachable)?;
+        Ok(match mode {
+            PwmMode::Servo | PwmMode::DutyCycle => PwmConfig::DutyCycle {
+                prescaler,
+                frequency,
+                channel_a: true,
+                channel_b: true,
+            },
+            PwmMode::Universal => PwmConfig::Universal {
+                prescaler,
+                frequency,
+                duty_cycle: frequency / 2,
+                channel_a: true,
+                channel_b: true,
+            },
+            PwmMode::Frequency => PwmConfig::Frequency {
+                prescaler,
+                channel_a: true,
+                channel_b: true,
+            },
+        })
+    }
+}
+
+#[test]
+fn test_for_frequency() {
+    let config = PwmConfig::for_frequency(1.0, PwmMode::DutyCycle).unwrap();
+    assert!(matches!(
+        config,
+        PwmConfig::DutyCycle {
+            prescaler: PwmPrescaler::Prescale62_5kHz,
+            frequency: 31250,
+            ..
+        }
+    ));
+
+    let config = PwmConfig::for_frequency(50.0, PwmMode::Universal).unwrap();
+    assert!(matches!(
+        config,
+        PwmConfig::Universal {
+            prescaler: PwmPrescaler::Prescale2MHz,
+            frequency: 20000,
+            ..
+        }
+    ));
+
+    let config = PwmConfig::for_frequency(20_000.0, PwmMode::Frequency).unwrap();
+    assert!(matches!(
+        config,
+        PwmConfig::Frequency {
+            prescaler: PwmPrescaler::Prescale16MHz,
+            ..
+        }
+    ));
+
+    assert!(PwmConfig::for_frequency(20_001.0, PwmMode::DutyCycle).is_err());
+    assert!(PwmConfig::for_frequency(0.1, PwmMode::DutyCycle).is_err());
+    assert!(PwmConfig::for_frequency(0.0, PwmMode::DutyCycle).is_err());
+    assert!(PwmConfig::for_frequency(60.0, PwmMode::Servo).is_err());
+}
+
+#[test]
+fn test_channel_settable() {
+    let universal = PwmConfig::for_frequency(1.0, PwmMode::Universal).unwrap();
+    assert!(universal.channel_settable(Channel::A));
+    assert!(!universal.channel_settable(Channel::B));
+
+    let duty_cycle = PwmConfig::for_frequency(1.0, PwmMode::DutyCycle).unwrap();
+    assert!(duty_cycle.channel_settable(Channel::B));
+    assert!(!PwmConfig::Deactivated.channel_settable(Channel::A));
+}
@@ -0,0 +1,160 @@
+use crate::{error::PiXtendError, Model};
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufReader, BufWriter, Error, ErrorKind, Read, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Appends the transferred frames to a file, see `PiXtend::record_to`. Each record consists of
+/// the timestamp in microseconds since the Unix epoch as a little endian `u64`, followed by the
+/// TX and the RX frame, each prefixed with its length as a little endian `u32`. The RX frame of
+/// a transfer without response is empty.
+pub(crate) struct Recorder {
+    writer: BufWriter<File>,
+    pub enabled: bool,
+}
+
+impl Recorder {
+    /// Opens the file for appending, creating it if necessary
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PiXtendError> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            writer: BufWriter::new(file),
+            enabled: true,
+        })
+    }
+
+    /// Appends one record with the given frames, unless the recording is paused
+    pub fn record(&mut self, tx: &[u8], rx: &[u8]) -> Result<(), PiXtendError> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_micros() as u64)
+            .unwrap_or_default();
+        self.writer.write_all(&timestamp.to_le_bytes())?;
+        for frame in [tx, rx] {
+            self.writer.write_all(&(frame.len() as u32).to_le_bytes())?;
+            self.writer.write_all(frame)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<(), PiXtendError> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+/// Iterator over the TX and RX frames of a recording, see `replay`
+pub struct Replay {
+    reader: BufReader<File>,
+    finished: bool,
+}
+
+/// Opens a recording made with `PiXtend::record_to` and returns an iterator over the recorded
+/// TX and RX frames in the order of transfer. The RX frames can be parsed with `Input`, or
+/// served by a transport to drive a test with a recorded session. A truncated last record, i.e.
+/// of a recording that was interrupted, ends the iteration. A record with a frame longer than
+/// the SPI frame of any model yields an error and ends the iteration, since the file is not a
+/// valid recording.
+/// Returns an error if the file can't be opened.
+pub fn replay(path: impl AsRef<Path>) -> Result<Replay, PiXtendError> {
+    Ok(Replay {
+        reader: BufReader::new(File::open(path)?),
+        finished: false,
+    })
+}
+
+impl Replay {
+    fn read_record(&mut self) -> std::io::Result<(Vec<u8>, Vec<u8>)> {
+        let mut timestamp = [0; 8];
+        self.reader.read_exact(&mut timestamp)?;
+        Ok((self.read_frame()?, self.read_frame()?))
+    }
+
+    fn read_frame(&mut self) -> std::io::Result<Vec<u8>> {
+        let mut len = [0; 4];
+        self.reader.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+
+        // The L model has the longest frame, so anything above is a corrupted length prefix
+        let max_len = Model::LModel.frame_length();
+        if len > max_len {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("recorded frame of {len} bytes exceeds the maximum of {max_len} bytes"),
+            ));
+        }
+
+        let mut frame = vec![0; len];
+        self.reader.read_exact(&mut frame)?;
+        Ok(frame)
+    }
+}
+
+impl Iterator for Replay {
+    type Item = Result<(Vec<u8>, Vec<u8>), PiXtendError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.read_record() {
+            Ok(record) => Some(Ok(record)),
+            // The end of the file and a truncated record both end the recording
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => {
+                self.finished = true;
+                None
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Err(error.into()))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_record_and_replay() {
+    let path = std::env::temp_dir().join(format!("pixtend-record-{}.bin", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut recorder = Recorder::open(&path).unwrap();
+    recorder.record(&[1, 2, 3], &[4, 5]).unwrap();
+    recorder.enabled = false;
+    recorder.record(&[6], &[7]).unwrap();
+    recorder.enabled = true;
+    recorder.record(&[], &[8; 111]).unwrap();
+    drop(recorder);
+
+    let records: Vec<_> = replay(&path).unwrap().collect::<Result<_, _>>().unwrap();
+    assert_eq!(
+        records,
+        [(vec![1, 2, 3], vec![4, 5]), (vec![], vec![8; 111])]
+    );
+
+    // A corrupted length prefix is reported instead of allocating the announced size
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&[0; 8]).unwrap();
+    file.write_all(&u32::MAX.to_le_bytes()).unwrap();
+    drop(file);
+    let mut records = replay(&path).unwrap().skip(2);
+    assert!(matches!(
+        records.next(),
+        Some(Err(PiXtendError::RecordingError(_)))
+    ));
+    assert!(records.next().is_none());
+    std::fs::remove_file(&path).unwrap();
+}
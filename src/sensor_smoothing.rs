@@ -0,0 +1,100 @@
+use crate::input::Sensor;
+use std::collections::VecDeque;
+
+/// Ring buffers of the last sensor readings per GPIO, used to return the median of the readings
+#[derive(Debug, Default)]
+pub struct SensorSmoothing {
+    samples: [VecDeque<Sensor>; 4],
+    sizes: [usize; 4],
+}
+
+impl SensorSmoothing {
+    /// Changes the number of buffered readings of the sensor with the given index, a size of `0`
+    /// or `1` disables the smoothing
+    pub fn set_size(&mut self, index: usize, size: usize) {
+        self.sizes[index] = size;
+        let capacity = match size {
+            0 | 1 => 0,
+            size => size,
+        };
+        while self.samples[index].len() > capacity {
+            self.samples[index].pop_front();
+        }
+    }
+
+    /// Records the readings of all sensors, readings of not responding sensors are skipped
+    pub fn push(&mut self, sensors: [&Sensor; 4]) {
+        for ((samples, size), sensor) in self.samples.iter_mut().zip(self.sizes).zip(sensors) {
+            if size <= 1 || sensor.is_empty() {
+                continue;
+            }
+
+            if samples.len() == size {
+                samples.pop_front();
+            }
+            samples.push_back(*sensor);
+        }
+    }
+
+    /// Drops the buffered readings, the buffer sizes are kept
+    pub fn clear(&mut self) {
+        self.samples.iter_mut().for_each(VecDeque::clear);
+    }
+
+    /// Returns the median of the plausible values of the buffered readings of the sensor with
+    /// the given index, or `None` if the smoothing is disabled or there is no plausible value
+    pub fn median(&self, index: usize, value: impl Fn(&Sensor) -> Option<f64>) -> Option<f64> {
+        let mut values = self.samples[index]
+            .iter()
+            .filter_map(value)
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            return None;
+        }
+
+        values.sort_by(f64::total_cmp);
+        let middle = values.len() / 2;
+        Some(match values.len() % 2 {
+            0 => (values[middle - 1] + values[middle]) / 2.0,
+            _ => values[middle],
+        })
+    }
+}
+
+#[test]
+fn test_sensor_smoothing() {
+    let reading = |temperature| Sensor {
+        temperature,
+        humidity: 500,
+    };
+    let value = |sensor: &Sensor| Some(sensor.temperature as f64);
+
+    let mut smoothing = SensorSmoothing::default();
+    smoothing.push([&reading(100); 4]);
+    assert_eq!(smoothing.median(0, value), None);
+
+    smoothing.set_size(0, 3);
+    smoothing.push([&reading(200); 4]);
+    assert_eq!(smoothing.median(0, value), Some(200.0));
+    smoothing.push([&reading(900); 4]);
+    assert_eq!(smoothing.median(0, value), Some(550.0));
+    smoothing.push([&reading(210); 4]);
+    smoothing.push(
+        [&Sensor {
+            temperature: 0,
+            humidity: 0,
+        }; 4],
+    );
+    smoothing.push([&reading(220); 4]);
+    assert_eq!(smoothing.median(0, value), Some(220.0));
+    assert_eq!(smoothing.median(1, value), None);
+
+    // Clearing drops the readings but keeps the size
+    smoothing.clear();
+    assert_eq!(smoothing.median(0, value), None);
+    smoothing.push([&reading(300); 4]);
+    assert_eq!(smoothing.median(0, value), Some(300.0));
+
+    smoothing.set_size(0, 1);
+    assert_eq!(smoothing.median(0, value), None);
+}
@@ -0,0 +1,225 @@
+use crate::{
+    error::PiXtendError,
+    input::Sensor,
+    output::Output,
+    transport::{input_frame, SpiTransport},
+    Model, SensorKind,
+};
+use deku::prelude::*;
+use std::sync::{Arc, Mutex};
+
+/// Firmware version reported by the simulated board
+const SIMULATED_FIRMWARE: u8 = 0;
+
+/// Hardware version reported by the simulated board
+const SIMULATED_HARDWARE: u8 = 21;
+
+type AnalogSource = Box<dyn FnMut() -> u16 + Send>;
+
+/// Transport that simulates a PiXtend V2 -L- instead of talking to real hardware. Every transfer
+/// answers with a valid input frame, which echoes the staged digital outputs onto the digital
+/// inputs, reads the analog inputs from user supplied functions and reports the configured
+/// sensor values. Clones share the same state, so the inputs can be changed while the transport
+/// is owned by a `PiXtend`.
+///
+/// # Example
+/// ```
+/// # use pixtend::{PiXtend, SimulationTransport};
+/// let simulation = SimulationTransport::default();
+/// simulation.set_analog_input(0, || 512).unwrap();
+///
+/// let mut pixtend =
+///     PiXtend::new_with_transport(simulation.clone(), SimulationTransport::default());
+/// pixtend.set_digital_output(3, true).unwrap();
+/// pixtend.read_write().unwrap();
+/// assert!(pixtend.get_digital_input(3).unwrap());
+/// ```
+#[derive(Clone, Default)]
+pub struct SimulationTransport {
+    state: Arc<Mutex<SimulationState>>,
+}
+
+#[derive(Default)]
+struct SimulationState {
+    digital_inputs: u16,
+    gpio_inputs: u8,
+    analog_inputs: [Option<AnalogSource>; 6],
+    sensors: [Option<Sensor>; 4],
+    digital_outputs: u16,
+    retain: Vec<u8>,
+}
+
+impl SimulationTransport {
+    /// Sets additional digital inputs as a bit mask, which are combined with the echoed outputs
+    pub fn set_digital_inputs(&self, mask: u16) {
+        self.lock().digital_inputs = mask;
+    }
+
+    /// Sets the GPIO inputs as a bit mask, where bit 0 corresponds to GPIO 0. Only the lower four
+    /// bits are used.
+    pub fn set_gpio_inputs(&self, mask: u8) {
+        self.lock().gpio_inputs = mask & 0x0F;
+    }
+
+    /// Drives the raw ADC value of the given analog voltage input from a function, which is
+    /// called once per transfer.
+    /// Valid indexes are `0` to `3`, returns an error if the index is invalid. The current inputs
+    /// `4` and `5` return `PiXtendError::InvalidAnalogCurrentInputIndex`, they are set via
+    /// `set_analog_current_input`.
+    pub fn set_analog_input<F>(&self, index: u8, source: F) -> Result<(), PiXtendError>
+    where
+        F: FnMut() -> u16 + Send + 'static,
+    {
+        match index {
+            0..=3 => {
+                self.lock().analog_inputs[index as usize] = Some(Box::new(source));
+                Ok(())
+            }
+            4..=5 => Err(PiXtendError::InvalidAnalogCurrentInputIndex(index)),
+            _ => Err(PiXtendError::InvalidAnalogVoltageInputIndex(index)),
+        }
+    }
+
+    /// Drives the raw ADC value of the given analog current input from a function, which is
+    /// called once per transfer.
+    /// Valid indexes are `4` and `5`, returns an error if the index is invalid.
+    pub fn set_analog_current_input<F>(&self, index: u8, source: F) -> Result<(), PiXtendError>
+    where
+        F: FnMut() -> u16 + Send + 'static,
+    {
+        if !(4..=5).contains(&index) {
+            return Err(PiXtendError::InvalidAnalogCurrentInputIndex(index));
+        }
+
+        self.lock().analog_inputs[index as usize] = Some(Box::new(source));
+        Ok(())
+    }
+
+    /// Reports the given temperature in Celsius and humidity fraction on a sensor GPIO
+    pub fn set_sensor(
+        &self,
+        index: u8,
+        sensor: SensorKind,
+        celsius: f64,
+        humidity: f64,
+    ) -> Result<(), PiXtendError> {
+        let mut state = self.lock();
+        let slot = state
+            .sensors
+            .get_mut(index as usize)
+            .ok_or(PiXtendError::InvalidGpioInputIndex(index))?;
+        *slot = Some(Sensor::from_values(sensor, celsius, humidity));
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SimulationState> {
+        // A panicking analog source must not take the whole simulation down
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SimulationState {
+    /// Stores the outputs of a written PiXtend frame, other frames like the DAC words are ignored
+    fn apply_output(&mut self, data: &[u8]) {
+        if data.len() != Model::LModel.frame_length() {
+            return;
+        }
+
+        if let Ok((_, output)) = Output::from_bytes((data, 0)) {
+            self.digital_outputs = output.data.digital_out.as_mask();
+            self.retain = output.data.retain.storage;
+        }
+    }
+
+    /// Builds the input frame from the current simulation state
+    fn input_frame(&mut self) -> Vec<u8> {
+        let header = [
+            SIMULATED_FIRMWARE,
+            SIMULATED_HARDWARE,
+            Model::LModel.byte(),
+            0b0000_0001,
+            0,
+            0,
+            0,
+        ];
+
+        let mut data = [0; 100];
+        let digital_inputs = self.digital_inputs | self.digital_outputs;
+        data[0..2].copy_from_slice(&digital_inputs.to_le_bytes());
+        for (index, source) in self.analog_inputs.iter_mut().enumerate() {
+            let value = source.as_mut().map(|source| source()).unwrap_or_default();
+            data[2 + index * 2..4 + index * 2].copy_from_slice(&value.to_le_bytes());
+        }
+        data[14] = self.gpio_inputs;
+        for (index, sensor) in self.sensors.iter().enumerate() {
+            if let Some(sensor) = sensor {
+                let offset = 15 + index * 4;
+                data[offset..offset + 2].copy_from_slice(&sensor.temperature.to_le_bytes());
+                data[offset + 2..offset + 4].copy_from_slice(&sensor.humidity.to_le_bytes());
+            }
+        }
+        let retain = self.retain.len().min(data.len() - 36);
+        data[36..36 + retain].copy_from_slice(&self.retain[..retain]);
+
+        input_frame(header, data)
+    }
+}
+
+impl SpiTransport for SimulationTransport {
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<usize, PiXtendError> {
+        let mut state = self.lock();
+        state.apply_output(write);
+        let frame = state.input_frame();
+        let len = frame.len().min(read.len());
+        read[..len].copy_from_slice(&frame[..len]);
+        Ok(len)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), PiXtendError> {
+        self.lock().apply_output(data);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_simulation_frame_crc() {
+    use crate::input::Input;
+
+    let simulation = SimulationTransport::default();
+    simulation.set_analog_current_input(5, || 0x0123).unwrap();
+    assert!(matches!(
+        simulation.set_analog_input(5, || 0),
+        Err(PiXtendError::InvalidAnalogCurrentInputIndex(5))
+    ));
+    assert!(matches!(
+        simulation.set_analog_current_input(3, || 0),
+        Err(PiXtendError::InvalidAnalogCurrentInputIndex(3))
+    ));
+    simulation
+        .set_sensor(1, SensorKind::DHT22, 21.5, 0.4)
+        .unwrap();
+
+    let mut output = Output::default();
+    output.data.digital_out.set_mask(0b1000_0000_0001).unwrap();
+    output.update().unwrap();
+
+    let mut read = [0; 111];
+    let mut transport = simulation.clone();
+    let len = transport
+        .transfer(&mut read, &output.to_bytes().unwrap())
+        .unwrap();
+    assert_eq!(len, 111);
+
+    let (_, input) = Input::from_bytes((read.as_ref(), 0)).unwrap();
+    assert!(input.check_crc_valid());
+    assert_eq!(input.data.digital_in.as_mask(), 0b1000_0000_0001);
+    assert_eq!(input.data.analog_in.in5, 0x0123);
+    assert_eq!(
+        input
+            .data
+            .sensor_in
+            .get_temperature_input(1, SensorKind::DHT22)
+            .unwrap(),
+        21.5
+    );
+}
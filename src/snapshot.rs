@@ -0,0 +1,17 @@
+use crate::Warnings;
+
+/// Values of all inputs of the PiXtend board, which are taken from the same input frame, see
+/// `PiXtend::snapshot`
+#[derive(Debug, Clone, Copy)]
+pub struct InputSnapshot {
+    /// Digital inputs, where bit N is the value of digital input N
+    pub digital_inputs: u16,
+    /// Analog voltage inputs `0` to `3` in volts
+    pub analog_voltages: [f64; 4],
+    /// Analog current inputs `4` and `5` in milliamperes, or in the unit of the calibration set
+    /// via `PiXtend::set_current_calibration`
+    pub analog_currents: [f64; 2],
+    /// GPIO inputs `0` to `3`, as reported by the board regardless of the GPIO configuration
+    pub gpio_inputs: [bool; 4],
+    pub warnings: Warnings,
+}
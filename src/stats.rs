@@ -0,0 +1,17 @@
+/// Counters for monitoring the health of the SPI link to the PiXtend board, which are updated on
+/// every cycle of `PiXtend::read_write`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CycleStats {
+    /// Number of performed SPI transfers, including the failed ones
+    pub cycles: u64,
+    /// Number of input frames with an invalid CRC
+    pub input_crc_errors: u64,
+    /// Number of cycles in which the PiXtend reported a CRC error of the output frame
+    pub output_crc_errors: u64,
+    /// Number of cycles in which the model of the PiXtend didn't match the configured model
+    pub model_mismatches: u64,
+    /// Number of cycles with a too short input frame or an output frame the PiXtend reported as
+    /// too short
+    pub short_frames: u64,
+}
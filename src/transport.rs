@@ -0,0 +1,97 @@
+use crate::error::PiXtendError;
+use rppal::spi::Spi;
+use std::sync::{Mutex, MutexGuard};
+
+/// Abstraction over the SPI bus used to communicate with the PiXtend board and the DAC. It is
+/// implemented for `rppal::spi::Spi`, but can be implemented for other transports, for example
+/// to test an application without real hardware.
+pub trait SpiTransport {
+    /// Writes the given data and reads the same amount of bytes into `read` at the same time.
+    /// Returns the number of bytes that were transferred.
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<usize, PiXtendError>;
+
+    /// Writes the given data without reading a response.
+    fn write(&mut self, data: &[u8]) -> Result<(), PiXtendError>;
+}
+
+impl SpiTransport for Spi {
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<usize, PiXtendError> {
+        Ok(Spi::transfer(self, read, write)?)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), PiXtendError> {
+        Spi::write(self, data)?;
+        Ok(())
+    }
+}
+
+/// Locks a transport shared with the blocking pool. A panic during a transfer leaves the
+/// transport usable, so the poisoning is ignored.
+pub(crate) fn lock<T>(transport: &Mutex<T>) -> MutexGuard<'_, T> {
+    transport.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Builds a 111 byte input frame with valid CRCs from the given header and data bytes
+pub(crate) fn input_frame(header: [u8; 7], data: [u8; 100]) -> Vec<u8> {
+    let header_crc = crate::utils::calc_crc16(header.into_iter());
+    let data_crc = crate::utils::calc_crc16(data.into_iter());
+
+    let mut frame = header.to_vec();
+    frame.extend(header_crc.to_le_bytes());
+    frame.extend(data);
+    frame.extend(data_crc.to_le_bytes());
+    frame
+}
+
+/// Transport for tests, which returns canned responses and records the written data
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    pub responses: std::collections::VecDeque<Vec<u8>>,
+    pub written: Vec<Vec<u8>>,
+}
+
+#[cfg(test)]
+impl MockTransport {
+    pub fn with_responses(responses: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        Self {
+            responses: responses.into_iter().collect(),
+            written: Vec::new(),
+        }
+    }
+
+    /// Builds a 111 byte input frame with valid CRCs from the given header and data bytes
+    pub fn input_frame(header: [u8; 7], data: [u8; 100]) -> Vec<u8> {
+        input_frame(header, data)
+    }
+}
+
+#[cfg(test)]
+impl crate::PiXtend<MockTransport> {
+    /// Builds an instance for tests, whose PiXtend SPI answers with the given frames in order,
+    /// with the cycle delay disabled
+    pub(crate) fn mocked(responses: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        let mut pixtend = Self::from_transports(
+            MockTransport::with_responses(responses),
+            MockTransport::default(),
+        );
+        pixtend.set_cycle_delay(std::time::Duration::ZERO);
+        pixtend
+    }
+}
+
+#[cfg(test)]
+impl SpiTransport for MockTransport {
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<usize, PiXtendError> {
+        self.written.push(write.to_vec());
+        let response = self.responses.pop_front().unwrap_or_default();
+        let len = response.len().min(read.len());
+        read[..len].copy_from_slice(&response[..len]);
+        Ok(len)
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<(), PiXtendError> {
+        self.written.push(data.to_vec());
+        Ok(())
+    }
+}
@@ -1,16 +1,64 @@
-pub fn calc_crc16(data: impl Iterator<Item = u8>) -> u16 {
-    let mut crc = 0xFFFF;
-    for byte in data {
-        crc ^= byte as u16;
+/// Lookup table for the CRC16 calculation with the reflected polynomial 0xA001
+static CRC_TABLE: [u16; 256] = generate_crc_table();
 
-        for _ in 0..8 {
+const fn generate_crc_table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u16;
+        let mut bit = 0;
+        while bit < 8 {
             if crc & 1 != 0 {
                 crc = (crc >> 1) ^ 0xA001;
             } else {
                 crc >>= 1;
             }
+            bit += 1;
         }
+
+        table[index] = crc;
+        index += 1;
+    }
+
+    table
+}
+
+pub fn calc_crc16(data: impl Iterator<Item = u8>) -> u16 {
+    let mut crc = 0xFFFF;
+    for byte in data {
+        crc = (crc >> 8) ^ CRC_TABLE[((crc ^ byte as u16) & 0xFF) as usize];
     }
 
     crc
 }
+
+/// Rounds half away from zero like `f64::round`, which is not available without `std`
+pub(crate) fn round(value: f64) -> f64 {
+    let truncated = value as i64 as f64;
+    match (value - truncated).abs() >= 0.5 {
+        true => truncated + value.signum(),
+        false => truncated,
+    }
+}
+
+/// Calculates the CRC16 (MODBUS) of the given data, which is the checksum the PiXtend firmware
+/// uses for its frames. This can be used to protect structured data inside the retain memory.
+pub fn crc16(data: &[u8]) -> u16 {
+    calc_crc16(data.iter().copied())
+}
+
+#[test]
+fn test_calc_crc16() {
+    assert_eq!(calc_crc16(b"123456789".iter().copied()), 0x4B37);
+    assert_eq!(calc_crc16([].into_iter()), 0xFFFF);
+    assert_eq!(crc16(b"123456789"), 0x4B37);
+    assert_eq!(CRC_TABLE[1], 0xC0C1);
+    assert_eq!(CRC_TABLE[255], 0x4040);
+}
+
+#[test]
+fn test_round() {
+    for value in [0.0, 0.4, 0.5, 1.49, 511.5, 1022.6, -0.5, -2.4, -2.6] {
+        assert_eq!(round(value), value.round());
+    }
+}
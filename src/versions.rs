@@ -0,0 +1,10 @@
+/// Firmware and hardware version as well as the model of the connected PiXtend board, as
+/// reported in the header of one input frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Versions {
+    pub firmware: u8,
+    pub hardware: u8,
+    /// Model character of the board, i.e. `'L'` for the PiXtend V2 -L-
+    pub model: char,
+}